@@ -0,0 +1,57 @@
+use std::io::Cursor;
+
+use plotters::prelude::*;
+
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 400;
+const BINS: usize = 30;
+
+/// Renders a histogram of `all` scores in grey with the `announced` scores overlaid in blue.
+/// Each histogram is scaled to its own peak so both shapes are visible.
+pub fn score_histogram_png(all: &[f64], announced: &[f64]) -> anyhow::Result<Vec<u8>> {
+    let lo = all.iter().copied().fold(f64::INFINITY, f64::min);
+    let hi = all.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let all_bins = histogram(all, lo, hi);
+    let announced_bins = histogram(announced, lo, hi);
+
+    let mut buf = vec![0u8; (WIDTH * HEIGHT * 3) as usize];
+    {
+        let root = BitMapBackend::with_buffer(&mut buf, (WIDTH, HEIGHT)).into_drawing_area();
+        root.fill(&WHITE)?;
+        let mut chart = ChartBuilder::on(&root)
+            .margin(10)
+            .build_cartesian_2d(0f64..BINS as f64, 0f64..1.05)?;
+        chart.draw_series(all_bins.iter().enumerate().map(|(i, &v)| {
+            let x = i as f64;
+            Rectangle::new([(x, 0.0), (x + 1.0, v)], RGBColor(190, 190, 190).filled())
+        }))?;
+        chart.draw_series(announced_bins.iter().enumerate().map(|(i, &v)| {
+            let x = i as f64;
+            Rectangle::new([(x + 0.25, 0.0), (x + 0.75, v)], BLUE.mix(0.7).filled())
+        }))?;
+        root.present()?;
+    }
+
+    let img = image::RgbImage::from_raw(WIDTH, HEIGHT, buf)
+        .ok_or_else(|| anyhow::anyhow!("chart buffer has the wrong size"))?;
+    let mut out = Cursor::new(Vec::new());
+    img.write_to(&mut out, image::ImageFormat::Png)?;
+    Ok(out.into_inner())
+}
+
+/// Bin counts of `values` over `lo..=hi`, scaled so the tallest bin is 1
+fn histogram(values: &[f64], lo: f64, hi: f64) -> [f64; BINS] {
+    let mut bins = [0f64; BINS];
+    let width = ((hi - lo) / BINS as f64).max(f64::EPSILON);
+    for &v in values {
+        let idx = (((v - lo) / width) as usize).min(BINS - 1);
+        bins[idx] += 1.0;
+    }
+    let peak = bins.iter().copied().fold(0.0, f64::max);
+    if peak > 0.0 {
+        for b in &mut bins {
+            *b /= peak;
+        }
+    }
+    bins
+}
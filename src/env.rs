@@ -1,29 +1,378 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+use serde::Deserialize;
+
 #[derive(Debug)]
 pub struct EnvCfg {
     pub discord_bot_token: String,
     pub announce_channel_id: u64,
     pub role_id: u64,
+    /// Commands are registered in just this server when set, instead of globally
+    pub guild_id: Option<u64>,
+    pub admin_role_id: Option<u64>,
     pub timezone: String,
     pub dict_path: String,
     pub state_path: String,
+    pub weights_path: Option<String>,
+    pub reuse_after_days: Option<i64>,
+    pub history_keep_days: Option<i64>,
+    pub date_format: Option<String>,
+    pub low_pool_threshold: usize,
+    pub mod_channel_id: Option<u64>,
+    pub audit_channel_id: Option<u64>,
+    pub public_view_path: Option<String>,
+    pub webhook_url: Option<String>,
+    pub reuse_oldest_when_exhausted: bool,
+    pub spoiler: bool,
+    pub suggester_cooldown_days: i64,
+    pub announce_jitter_minutes: i64,
+    pub announce_spacing_secs: u64,
+    pub fold_accents: bool,
+    pub command_cooldown_secs: u64,
+    pub discouraged_path: Option<String>,
+    pub discouraged_penalty: f64,
+    pub announce_lead_days: i64,
+    pub recent_letter_window: usize,
+    pub recent_letter_penalty: f64,
+    pub guilds_path: Option<String>,
+    pub announce_thread_name: Option<String>,
+    pub easy_mode: bool,
+    pub credit_display_names: bool,
+    pub quiet_credit: bool,
+    pub show_streak: bool,
+    pub slow_reveal: bool,
+    pub reveal: bool,
+    pub proper_nouns_path: Option<String>,
+    pub dry_run: bool,
+    pub alphabet: Option<String>,
+    pub freq_path: Option<String>,
+    pub queue_cap_per_user: usize,
+    pub queue_aging: bool,
+    pub reuse_requests: bool,
+    pub rejection_memory_secs: u64,
+    pub skip_weekdays: Vec<chrono::Weekday>,
+    pub surprise_weekdays: Vec<chrono::Weekday>,
+    pub digest_weekday: Option<chrono::Weekday>,
+    pub no_word_message: Option<String>,
+    pub announce_reactions: Vec<serenity::all::ReactionType>,
+    pub decisions_path: Option<String>,
+    pub webhook_name: Option<String>,
+    pub webhook_avatar_url: Option<String>,
+    pub test_channel_id: Option<u64>,
+    pub test_state_path: Option<String>,
+}
+
+/// Per-server settings, the env vars describe one server and `GUILDS_PATH` can add more
+#[derive(Debug, Deserialize)]
+pub struct GuildCfg {
+    /// Resolved from the announce channel when omitted
+    #[serde(default)]
+    pub guild_id: Option<u64>,
+    pub announce_channel_id: u64,
+    pub role_id: u64,
+    pub state_path: String,
+    #[serde(default)]
+    pub mod_channel_id: Option<u64>,
+    #[serde(default)]
+    pub audit_channel_id: Option<u64>,
+    /// Where announced words are published as JSON, see `state::PublicView`
+    #[serde(default)]
+    pub public_view_path: Option<String>,
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// `TEST_CHANNEL_ID`, set on every server rather than read from `GUILDS_PATH`
+    #[serde(skip)]
+    pub test_channel_id: Option<u64>,
 }
 
 impl EnvCfg {
     pub fn from_env() -> anyhow::Result<Self> {
         dotenvy::dotenv().ok();
-        let discord_bot_token = std::env::var("DISCORD_BOT_TOKEN")?;
-        let announce_channel_id = std::env::var("ANNOUNCE_CHANNEL_ID")?.parse()?;
-        let role_id = std::env::var("WORDLE_ROLE_ID")?.parse()?;
-        let timezone = std::env::var("TIMEZONE")?;
-        let dict_path = std::env::var("DICT_PATH")?;
-        let state_path = std::env::var("STATE_PATH")?;
+        let vars = Vars::load()?;
+        let discord_bot_token = vars.required("DISCORD_BOT_TOKEN")?;
+        let announce_channel_id = vars.required("ANNOUNCE_CHANNEL_ID")?.parse()?;
+        let role_id = vars.required("WORDLE_ROLE_ID")?.parse()?;
+        let guild_id = optional(&vars, "GUILD_ID")?;
+        let admin_role_id = optional(&vars, "ADMIN_ROLE_ID")?;
+        let timezone = vars.required("TIMEZONE")?;
+        let dict_path = vars.required("DICT_PATH")?;
+        let state_path = vars.required("STATE_PATH")?;
+        let test_channel_id = optional(&vars, "TEST_CHANNEL_ID")?;
+        let test_state_path = vars.get("TEST_STATE_PATH");
+        let weights_path = vars.get("WEIGHTS_PATH");
+        let reuse_after_days = optional(&vars, "REUSE_AFTER_DAYS")?;
+        let history_keep_days = optional(&vars, "HISTORY_KEEP_DAYS")?;
+        let date_format = vars.get("DATE_FORMAT");
+        let low_pool_threshold = optional(&vars, "LOW_POOL_THRESHOLD")?.unwrap_or(30);
+        let mod_channel_id = optional(&vars, "MOD_CHANNEL_ID")?;
+        let audit_channel_id = optional(&vars, "AUDIT_CHANNEL_ID")?;
+        let public_view_path = vars.get("PUBLIC_VIEW_PATH");
+        let webhook_url = vars.get("WEBHOOK_URL");
+        let reuse_oldest_when_exhausted =
+            optional(&vars, "REUSE_OLDEST_WHEN_EXHAUSTED")?.unwrap_or(true);
+        let spoiler = optional(&vars, "SPOILER")?.unwrap_or(true);
+        let suggester_cooldown_days = optional(&vars, "SUGGESTER_COOLDOWN_DAYS")?.unwrap_or(0);
+        let announce_jitter_minutes = optional(&vars, "ANNOUNCE_JITTER_MINUTES")?.unwrap_or(0);
+        let announce_spacing_secs = optional(&vars, "ANNOUNCE_SPACING_SECS")?.unwrap_or(2);
+        let fold_accents = optional(&vars, "FOLD_ACCENTS")?.unwrap_or(false);
+        let command_cooldown_secs = optional(&vars, "COMMAND_COOLDOWN_SECS")?.unwrap_or(5);
+        let discouraged_path = vars.get("DISCOURAGED_PATH");
+        let discouraged_penalty = optional(&vars, "DISCOURAGED_PENALTY")?.unwrap_or(5.0);
+        let announce_lead_days = optional(&vars, "ANNOUNCE_LEAD_DAYS")?.unwrap_or(1);
+        if announce_lead_days < 0 {
+            anyhow::bail!("ANNOUNCE_LEAD_DAYS can't be negative, got {announce_lead_days}");
+        }
+        let recent_letter_window = optional(&vars, "RECENT_LETTER_WINDOW")?.unwrap_or(0);
+        let recent_letter_penalty = optional(&vars, "RECENT_LETTER_PENALTY")?.unwrap_or(0.5);
+        let guilds_path = vars.get("GUILDS_PATH");
+        let announce_thread_name = vars.get("ANNOUNCE_THREAD_NAME");
+        let easy_mode = optional(&vars, "EASY_MODE")?.unwrap_or(false);
+        let credit_display_names = optional(&vars, "CREDIT_DISPLAY_NAMES")?.unwrap_or(false);
+        let quiet_credit = optional(&vars, "QUIET_CREDIT")?.unwrap_or(false);
+        let show_streak = optional(&vars, "SHOW_STREAK")?.unwrap_or(false);
+        let slow_reveal = optional(&vars, "SLOW_REVEAL")?.unwrap_or(false);
+        let reveal = optional(&vars, "REVEAL")?.unwrap_or(false);
+        let proper_nouns_path = vars.get("PROPER_NOUNS_PATH");
+        let dry_run = optional(&vars, "DRY_RUN")?.unwrap_or(false);
+        let alphabet = vars.get("ALPHABET");
+        let freq_path = vars.get("FREQ_PATH");
+        let queue_cap_per_user = optional(&vars, "QUEUE_CAP_PER_USER")?.unwrap_or(0);
+        let queue_aging = optional(&vars, "QUEUE_AGING")?.unwrap_or(false);
+        let reuse_requests = optional(&vars, "REUSE_REQUESTS")?.unwrap_or(false);
+        let rejection_memory_secs = optional(&vars, "REJECTION_MEMORY_SECS")?.unwrap_or(0);
+        let no_word_message = vars.get("NO_WORD_MESSAGE");
+        let decisions_path = vars.get("DECISIONS_PATH");
+        let webhook_name = vars.get("WEBHOOK_NAME");
+        let webhook_avatar_url = vars.get("WEBHOOK_AVATAR_URL");
+        let skip_weekdays = weekdays(&vars, "SKIP_WEEKDAYS")?;
+        let surprise_weekdays = weekdays(&vars, "SURPRISE_WEEKDAYS")?;
+        let digest_weekday = match weekdays(&vars, "DIGEST_WEEKDAY")?.as_slice() {
+            [] => None,
+            [day] => Some(*day),
+            _ => anyhow::bail!("DIGEST_WEEKDAY takes a single day"),
+        };
+        let announce_reactions = match vars.get("ANNOUNCE_REACTIONS") {
+            Some(v) => v
+                .split(',')
+                .map(str::trim)
+                .filter(|e| !e.is_empty())
+                .map(|e| {
+                    e.parse().map_err(|_| {
+                        anyhow::anyhow!("parsing ANNOUNCE_REACTIONS: invalid emoji `{e}`")
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?,
+            None => Vec::new(),
+        };
         Ok(Self {
             discord_bot_token,
             announce_channel_id,
             role_id,
+            guild_id,
+            admin_role_id,
             timezone,
             dict_path,
             state_path,
+            weights_path,
+            reuse_after_days,
+            history_keep_days,
+            date_format,
+            low_pool_threshold,
+            mod_channel_id,
+            audit_channel_id,
+            public_view_path,
+            webhook_url,
+            reuse_oldest_when_exhausted,
+            spoiler,
+            suggester_cooldown_days,
+            announce_jitter_minutes,
+            announce_spacing_secs,
+            fold_accents,
+            command_cooldown_secs,
+            discouraged_path,
+            discouraged_penalty,
+            announce_lead_days,
+            recent_letter_window,
+            recent_letter_penalty,
+            guilds_path,
+            announce_thread_name,
+            easy_mode,
+            credit_display_names,
+            quiet_credit,
+            show_streak,
+            slow_reveal,
+            reveal,
+            proper_nouns_path,
+            dry_run,
+            alphabet,
+            freq_path,
+            queue_cap_per_user,
+            queue_aging,
+            reuse_requests,
+            rejection_memory_secs,
+            skip_weekdays,
+            surprise_weekdays,
+            digest_weekday,
+            no_word_message,
+            announce_reactions,
+            decisions_path,
+            webhook_name,
+            webhook_avatar_url,
+            test_channel_id,
+            test_state_path,
         })
     }
+
+    /// The server configured by env vars, followed by any listed in `GUILDS_PATH`
+    pub fn guild_cfgs(&self) -> anyhow::Result<Vec<GuildCfg>> {
+        // TEST_STATE_PATH only stands in for STATE_PATH, so other servers would run on their
+        // production state
+        if self.test_channel_id.is_some() && self.guilds_path.is_some() {
+            anyhow::bail!("TEST_CHANNEL_ID can't be used with GUILDS_PATH");
+        }
+        let state_path = match (self.test_channel_id, &self.test_state_path) {
+            (Some(_), Some(path)) => path.clone(),
+            _ => self.state_path.clone(),
+        };
+        let mut guilds = vec![GuildCfg {
+            guild_id: self.guild_id,
+            announce_channel_id: self.announce_channel_id,
+            role_id: self.role_id,
+            state_path,
+            mod_channel_id: self.mod_channel_id,
+            audit_channel_id: self.audit_channel_id,
+            public_view_path: self.public_view_path.clone(),
+            webhook_url: self.webhook_url.clone(),
+            test_channel_id: None,
+        }];
+        if let Some(path) = &self.guilds_path {
+            let bytes = std::fs::read(path).with_context(|| format!("reading {path}"))?;
+            let extra: Vec<GuildCfg> =
+                serde_json::from_slice(&bytes).with_context(|| format!("parsing {path}"))?;
+            guilds.extend(extra);
+        }
+        if let Some(id) = self.test_channel_id {
+            for guild in &mut guilds {
+                guild.redirect_to(id);
+            }
+        }
+        Ok(guilds)
+    }
+}
+
+impl GuildCfg {
+    /// Sends announcements and any mod or audit notices to the `TEST_CHANNEL_ID` channel
+    fn redirect_to(&mut self, test_channel_id: u64) {
+        self.test_channel_id = Some(test_channel_id);
+        if self.mod_channel_id.is_some() {
+            self.mod_channel_id = Some(test_channel_id);
+        }
+        if self.audit_channel_id.is_some() {
+            self.audit_channel_id = Some(test_channel_id);
+        }
+    }
+}
+
+/// Settings from the environment, falling back to the `CONFIG_PATH` file for anything unset
+struct Vars {
+    file: HashMap<String, String>,
+}
+
+/// A `CONFIG_PATH` value, keyed by the same name as its env var. Lists become comma separated
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FileValue {
+    Bool(bool),
+    Number(serde_json::Number),
+    String(String),
+    List(Vec<String>),
+}
+
+impl Vars {
+    fn load() -> anyhow::Result<Self> {
+        let Ok(path) = std::env::var("CONFIG_PATH") else {
+            return Ok(Self {
+                file: HashMap::new(),
+            });
+        };
+        let bytes = std::fs::read(&path).with_context(|| format!("reading {path}"))?;
+        let values: HashMap<String, FileValue> =
+            serde_json::from_slice(&bytes).with_context(|| format!("parsing {path}"))?;
+        let file = values
+            .into_iter()
+            .map(|(k, v)| {
+                let v = match v {
+                    FileValue::Bool(b) => b.to_string(),
+                    FileValue::Number(n) => n.to_string(),
+                    FileValue::String(s) => s,
+                    FileValue::List(l) => l.join(","),
+                };
+                (k, v)
+            })
+            .collect();
+        Ok(Self { file })
+    }
+
+    fn get(&self, key: &str) -> Option<String> {
+        std::env::var(key)
+            .ok()
+            .or_else(|| self.file.get(key).cloned())
+    }
+
+    fn required(&self, key: &str) -> anyhow::Result<String> {
+        self.get(key)
+            .with_context(|| format!("{key} must be set in the environment or CONFIG_PATH"))
+    }
+}
+
+/// Reads a comma separated list of weekdays, empty if unset
+fn weekdays(vars: &Vars, key: &str) -> anyhow::Result<Vec<chrono::Weekday>> {
+    let Some(v) = vars.get(key) else {
+        return Ok(Vec::new());
+    };
+    v.split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| {
+            d.parse()
+                .map_err(|_| anyhow::anyhow!("parsing {key}: unknown day `{d}`"))
+        })
+        .collect()
+}
+
+/// Reads and parses a setting that may be unset
+fn optional<T>(vars: &Vars, key: &str) -> anyhow::Result<Option<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    vars.get(key)
+        .map(|v| v.parse().with_context(|| format!("parsing {key}")))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_takes_every_post() {
+        let mut guild = GuildCfg {
+            guild_id: None,
+            announce_channel_id: 1,
+            role_id: 2,
+            state_path: "state.json".into(),
+            mod_channel_id: Some(3),
+            audit_channel_id: None,
+            public_view_path: None,
+            webhook_url: None,
+            test_channel_id: None,
+        };
+        guild.redirect_to(9);
+        assert_eq!(guild.test_channel_id, Some(9));
+        assert_eq!(guild.mod_channel_id, Some(9));
+        assert_eq!(guild.audit_channel_id, None);
+        assert_eq!(guild.announce_channel_id, 1);
+    }
 }
@@ -0,0 +1,29 @@
+/// Why `run_once` couldn't produce an announcement
+#[derive(Debug, thiserror::Error)]
+pub enum SelectError {
+    #[error("the dictionary has no words")]
+    DictionaryEmpty,
+    #[error("every dictionary word has already been used")]
+    PoolExhausted,
+    #[error("the webhook didn't return the posted message")]
+    WebhookNoMessage,
+    #[error("failed to post the announcement")]
+    AnnounceFailed(#[source] Box<serenity::Error>),
+}
+
+impl SelectError {
+    /// Whether trying again shortly could succeed. An empty dictionary or exhausted pool won't
+    /// fix itself, a failed post might
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::DictionaryEmpty | Self::PoolExhausted => false,
+            Self::WebhookNoMessage | Self::AnnounceFailed(_) => true,
+        }
+    }
+}
+
+impl From<serenity::Error> for SelectError {
+    fn from(e: serenity::Error) -> Self {
+        Self::AnnounceFailed(Box::new(e))
+    }
+}
@@ -1,29 +1,294 @@
-use std::{collections::HashMap, fs, path::Path, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
 
-use chrono::{Datelike, TimeZone};
+use anyhow::Context;
+use chrono::{Datelike, NaiveDate, TimeZone};
 use chrono_tz::Tz;
+use parking_lot::RwLock;
 use poise::CreateReply;
-use serenity::all::{ChannelId, ClientBuilder, GatewayIntents, RoleId};
+use serenity::all::{ChannelId, ClientBuilder, GatewayIntents, GuildId, RoleId, UserId};
 use tokio::time::{Instant, sleep_until};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
-use crate::state::Store;
+use crate::{
+    env::GuildCfg,
+    error::SelectError,
+    sink::{AnnounceSink, ConfiguredSink, MemberNames},
+    state::{BotState, PickSource, PostedMessage, QueuedWord, RejectionCounts, Store},
+    words::{Corpus, DictOptions, Weights, WordFreq},
+};
 
+#[cfg(feature = "charts")]
+mod chart;
 mod env;
+mod error;
+mod sink;
 mod state;
 mod words;
 
 type Ctx<'a> = poise::Context<'a, AppState, anyhow::Error>;
 
 const SAMPLE_ALPHA: f64 = 2.0;
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+/// Read commands that can build large replies, rate limited per user by `COMMAND_COOLDOWN_SECS`
+const COOLDOWN_COMMANDS: &[&str] = &["history", "unused", "onday", "letterdrought"];
+/// How long a looked up nickname is reused before asking Discord again
+const MEMBER_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+/// Most nicknames kept per server, the least recently fetched goes first
+const MEMBER_CACHE_CAP: usize = 500;
 
 #[derive(Clone)]
 pub struct AppState {
-    store: Arc<Store>,
+    guilds: Arc<HashMap<GuildId, Arc<GuildState>>>,
     timezone: Tz,
+    admin_role_id: Option<RoleId>,
+    dictionary: Arc<RwLock<Arc<HashMap<String, f64>>>>,
+    /// Every dictionary score in ascending order, kept in step with `dictionary` by `rescore`
+    sorted_scores: Arc<RwLock<Arc<Vec<f64>>>>,
+    weights: Arc<RwLock<Weights>>,
+    weights_path: Option<String>,
+    corpus: Arc<Corpus>,
+    dict_path: Arc<str>,
+    reuse_after_days: Option<i64>,
+    history_keep_days: Option<i64>,
+    date_format: Arc<str>,
+    low_pool_threshold: usize,
+    reuse_oldest_when_exhausted: bool,
+    spoiler: bool,
+    suggester_cooldown_days: i64,
+    announce_jitter_minutes: i64,
+    /// Wait between servers' announcements, so a bot in many servers doesn't hit them all at once
+    announce_spacing: std::time::Duration,
+    dict_options: Arc<DictOptions>,
+    announce_lead_days: i64,
+    started_at: std::time::Instant,
+    recent_letter_window: usize,
+    recent_letter_penalty: f64,
+    announce_thread_name: Option<String>,
+    easy_mode: bool,
+    credit_display_names: bool,
+    /// Credit suggesters with a mention that doesn't notify them
+    quiet_credit: bool,
+    show_streak: bool,
+    /// Announce a letter at a time by editing the message
+    slow_reveal: bool,
+    reveal: bool,
+    dry_run: bool,
+    queue_cap_per_user: usize,
+    /// Queued words passed over for a suggester's cooldown go first next time
+    queue_aging: bool,
+    /// Note suggestions of used words as requests to have them back, with `REUSE_AFTER_DAYS`
+    reuse_requests: bool,
+    /// How long a rejected suggestion is remembered per user, zero to not remember
+    rejection_memory: std::time::Duration,
+    /// Days with no puzzle, never announced or targeted
+    skip_weekdays: Arc<HashSet<chrono::Weekday>>,
+    surprise_weekdays: Arc<HashSet<chrono::Weekday>>,
+    digest_weekday: Option<chrono::Weekday>,
+    no_word_message: Option<String>,
+    announce_reactions: Arc<Vec<serenity::all::ReactionType>>,
+    decisions_path: Option<String>,
+    webhook_name: Option<String>,
+    webhook_avatar_url: Option<String>,
+}
+
+/// Everything specific to one server: its saved state and where to announce
+pub struct GuildState {
+    id: GuildId,
+    store: Store,
     channel_id: ChannelId,
-    role_id: RoleId,
-    dictionary: Arc<HashMap<String, f64>>,
+    /// `None` when the role id is configured as 0, announcements then ping nobody
+    role_id: Option<RoleId>,
+    mod_channel_id: Option<ChannelId>,
+    audit_channel_id: Option<ChannelId>,
+    public_view_path: Option<String>,
+    /// Announcements go through this webhook instead of the bot when set
+    webhook_url: Option<String>,
+    /// `TEST_CHANNEL_ID`, which takes every announcement in place of the real channel
+    test_channel_id: Option<ChannelId>,
+    /// Mirrors `BotState::paused` so the scheduler can check it without the store lock
+    paused: AtomicBool,
+    /// Held for the whole of `run_once` so concurrent runs can't pick twice for one date
+    run_lock: tokio::sync::Mutex<()>,
+    /// When each user last had a word turned down and why, kept for `REJECTION_MEMORY_SECS`
+    recent_rejections: parking_lot::Mutex<HashMap<(UserId, String), (std::time::Instant, String)>>,
+    /// Display names from member lookups, kept for `MEMBER_CACHE_TTL`
+    display_names: parking_lot::Mutex<HashMap<UserId, (String, std::time::Instant)>>,
+}
+
+impl GuildState {
+    fn load(id: GuildId, cfg: &GuildCfg) -> anyhow::Result<Self> {
+        let state_path = Path::new(&cfg.state_path);
+        if let Some(parent) = state_path.parent() {
+            fs::create_dir_all(parent).ok();
+        }
+        let store = Store::new(cfg.state_path.clone());
+        store.load()?;
+        let paused = AtomicBool::new(store.with(|s| s.paused));
+        Ok(Self {
+            id,
+            store,
+            channel_id: ChannelId::new(cfg.announce_channel_id),
+            role_id: (cfg.role_id != 0).then(|| RoleId::new(cfg.role_id)),
+            mod_channel_id: cfg.mod_channel_id.map(ChannelId::new),
+            audit_channel_id: cfg.audit_channel_id.map(ChannelId::new),
+            public_view_path: cfg.public_view_path.clone(),
+            webhook_url: cfg.webhook_url.clone(),
+            test_channel_id: cfg.test_channel_id.map(ChannelId::new),
+            paused,
+            run_lock: tokio::sync::Mutex::default(),
+            recent_rejections: parking_lot::Mutex::default(),
+            display_names: parking_lot::Mutex::default(),
+        })
+    }
+
+    /// Where announcements go: the test channel, the `/setchannel` choice, or the configured
+    /// channel
+    fn announce_channel(&self) -> ChannelId {
+        if let Some(id) = self.test_channel_id {
+            return id;
+        }
+        self.store
+            .with(|s| s.announce_channel_id)
+            .unwrap_or(self.channel_id)
+    }
+
+    /// The reason `user` was given for `word`, if they tried it within `ttl`
+    fn recent_rejection(
+        &self,
+        user: UserId,
+        word: &str,
+        ttl: std::time::Duration,
+    ) -> Option<String> {
+        let recent = self.recent_rejections.lock();
+        let (at, reason) = recent.get(&(user, word.to_owned()))?;
+        (at.elapsed() < ttl).then(|| reason.clone())
+    }
+
+    fn remember_rejection(
+        &self,
+        user: UserId,
+        word: &str,
+        reason: String,
+        ttl: std::time::Duration,
+    ) {
+        let mut recent = self.recent_rejections.lock();
+        recent.retain(|_, (at, _)| at.elapsed() < ttl);
+        recent.insert((user, word.to_owned()), (std::time::Instant::now(), reason));
+    }
+
+    /// `user`'s server nickname, from the cache while it's fresh or else looked up
+    async fn display_name(
+        &self,
+        ctx: &serenity::all::Context,
+        user: UserId,
+    ) -> serenity::Result<String> {
+        if let Some((name, at)) = self.display_names.lock().get(&user)
+            && at.elapsed() < MEMBER_CACHE_TTL
+        {
+            return Ok(name.clone());
+        }
+        let name = self.id.member(ctx, user).await?.display_name().to_owned();
+
+        let mut names = self.display_names.lock();
+        names.retain(|_, (_, at)| at.elapsed() < MEMBER_CACHE_TTL);
+        if names.len() >= MEMBER_CACHE_CAP
+            && let Some(oldest) = names.iter().min_by_key(|(_, (_, at))| *at).map(|(u, _)| *u)
+        {
+            names.remove(&oldest);
+        }
+        names.insert(user, (name.clone(), std::time::Instant::now()));
+        Ok(name)
+    }
+}
+
+impl AppState {
+    /// Snapshot of the current dictionary, cheap to clone and safe to hold across awaits
+    fn dict(&self) -> Arc<HashMap<String, f64>> {
+        self.dictionary.read().clone()
+    }
+
+    fn current_weights(&self) -> Weights {
+        *self.weights.read()
+    }
+
+    /// Rebuilds the dictionary scores using the current weights
+    fn rescore(&self) {
+        let dict = self
+            .corpus
+            .rescore(self.current_weights(), &self.dict_options);
+        let scores = sorted_scores(&dict);
+        *self.dictionary.write() = Arc::new(dict);
+        *self.sorted_scores.write() = Arc::new(scores);
+    }
+
+    /// How many dictionary words score below `score`
+    fn words_below(&self, score: f64) -> usize {
+        self.sorted_scores.read().partition_point(|&s| s < score)
+    }
+
+    /// The date in the bot's timezone
+    fn today(&self) -> NaiveDate {
+        self.timezone
+            .from_utc_datetime(&chrono::Utc::now().naive_utc())
+            .date_naive()
+    }
+
+    /// The date the next announcement is for, moved past any skipped weekdays
+    fn target_date(&self) -> NaiveDate {
+        self.target_date_from(self.today())
+    }
+
+    /// `target_date` as it would be on `today`
+    fn target_date_from(&self, today: NaiveDate) -> NaiveDate {
+        self.next_puzzle_date(today + chrono::Duration::days(self.announce_lead_days))
+    }
+
+    /// `date`, or the first day after it that isn't a skipped weekday
+    fn next_puzzle_date(&self, mut date: NaiveDate) -> NaiveDate {
+        while self.skip_weekdays.contains(&date.weekday()) {
+            date += chrono::Duration::days(1);
+        }
+        date
+    }
+
+    /// The `n` puzzle dates from `first` on, leaving out skipped weekdays
+    fn puzzle_dates(&self, first: NaiveDate, n: usize) -> Vec<NaiveDate> {
+        std::iter::successors(Some(self.next_puzzle_date(first)), |d| {
+            Some(self.next_puzzle_date(*d + chrono::Duration::days(1)))
+        })
+        .take(n)
+        .collect()
+    }
+
+    /// The date tonight's run would announce if no weekdays were skipped
+    fn unskipped_target_date(&self) -> NaiveDate {
+        self.today() + chrono::Duration::days(self.announce_lead_days)
+    }
+
+    /// Words that can't be picked for `target`: every used word, or only those inside the reuse window
+    fn excluded(&self, s: &BotState, target: NaiveDate) -> HashSet<String> {
+        match self.reuse_after_days {
+            Some(days) => s.used_since(target - chrono::Duration::days(days)),
+            None => s.used(),
+        }
+    }
+
+    /// How many dictionary words could still be picked for `target`
+    fn unused_count(&self, s: &BotState, target: NaiveDate) -> usize {
+        let excluded = self.excluded(s, target);
+        self.dict()
+            .keys()
+            .filter(|w| !excluded.contains(*w))
+            .count()
+    }
 }
 
 #[tokio::main]
@@ -37,39 +302,197 @@ async fn main() -> anyhow::Result<()> {
 
     let cfg = env::EnvCfg::from_env()?;
 
-    let state_path = Path::new(&cfg.state_path);
-    if let Some(parent) = state_path.parent() {
-        fs::create_dir_all(parent).ok();
-    }
-    let store = Arc::new(Store::new(cfg.state_path));
-    store.load()?;
+    // guilds without an id are resolved from their announce channel once connected
+    let pending_guilds = cfg.guild_cfgs()?;
 
     let timezone: Tz = cfg.timezone.parse().expect("Invalid IANA timezone");
+    let skip_weekdays: HashSet<chrono::Weekday> = cfg.skip_weekdays.iter().copied().collect();
+    anyhow::ensure!(
+        skip_weekdays.len() < 7,
+        "SKIP_WEEKDAYS can't skip every day of the week"
+    );
+
+    let admin_role_id = cfg.admin_role_id.map(RoleId::new);
 
-    let channel_id = ChannelId::new(cfg.announce_channel_id);
-    let role_id = RoleId::new(cfg.role_id);
+    let date_format = cfg
+        .date_format
+        .unwrap_or_else(|| DEFAULT_DATE_FORMAT.to_owned());
+    validate_date_format(&date_format)?;
 
-    let dictionary = Arc::new(words::build_dict(cfg.dict_path)?);
+    let wt = match &cfg.weights_path {
+        Some(path) => Weights::load(path)?,
+        None => Weights::default(),
+    };
+    let discouraged = match &cfg.discouraged_path {
+        Some(path) => {
+            let list = words::load_word_list(path, cfg.fold_accents)?;
+            info!("Loaded {} discouraged words", list.len());
+            list
+        }
+        None => HashSet::new(),
+    };
+    let word_freq = match &cfg.freq_path {
+        Some(path) => {
+            let freq = WordFreq::load(path, cfg.fold_accents)?;
+            info!("Loaded frequencies for {} words", freq.len());
+            Some(freq)
+        }
+        None => None,
+    };
+    let proper_nouns = match &cfg.proper_nouns_path {
+        Some(path) => words::load_word_list(path, cfg.fold_accents)?,
+        None => HashSet::new(),
+    };
+    let dict_options = DictOptions {
+        fold_accents: cfg.fold_accents,
+        discouraged,
+        discouraged_penalty: cfg.discouraged_penalty,
+        proper_nouns,
+        alphabet: cfg.alphabet.map(|a| a.to_lowercase().chars().collect()),
+        word_freq,
+    };
+    let corpus = Corpus::load(&cfg.dict_path, &dict_options)?;
+    let dictionary = Arc::new(corpus.rescore(wt, &dict_options));
 
-    let state = AppState {
-        store,
+    let mut state = AppState {
+        guilds: Arc::default(),
         timezone,
-        channel_id,
-        role_id,
-        dictionary,
+        admin_role_id,
+        sorted_scores: Arc::new(RwLock::new(Arc::new(sorted_scores(&dictionary)))),
+        dictionary: Arc::new(RwLock::new(dictionary)),
+        weights: Arc::new(RwLock::new(wt)),
+        weights_path: cfg.weights_path,
+        corpus: Arc::new(corpus),
+        dict_path: cfg.dict_path.into(),
+        reuse_after_days: cfg.reuse_after_days,
+        history_keep_days: cfg.history_keep_days,
+        date_format: date_format.into(),
+        low_pool_threshold: cfg.low_pool_threshold,
+        reuse_oldest_when_exhausted: cfg.reuse_oldest_when_exhausted,
+        spoiler: cfg.spoiler,
+        suggester_cooldown_days: cfg.suggester_cooldown_days,
+        announce_jitter_minutes: cfg.announce_jitter_minutes,
+        announce_spacing: std::time::Duration::from_secs(cfg.announce_spacing_secs),
+        dict_options: Arc::new(dict_options),
+        announce_lead_days: cfg.announce_lead_days,
+        started_at: std::time::Instant::now(),
+        recent_letter_window: cfg.recent_letter_window,
+        recent_letter_penalty: cfg.recent_letter_penalty,
+        announce_thread_name: cfg.announce_thread_name,
+        easy_mode: cfg.easy_mode,
+        credit_display_names: cfg.credit_display_names,
+        quiet_credit: cfg.quiet_credit,
+        show_streak: cfg.show_streak,
+        slow_reveal: cfg.slow_reveal,
+        reveal: cfg.reveal,
+        dry_run: cfg.dry_run,
+        queue_cap_per_user: cfg.queue_cap_per_user,
+        queue_aging: cfg.queue_aging,
+        reuse_requests: cfg.reuse_requests,
+        rejection_memory: std::time::Duration::from_secs(cfg.rejection_memory_secs),
+        skip_weekdays: Arc::new(skip_weekdays),
+        surprise_weekdays: Arc::new(cfg.surprise_weekdays.iter().copied().collect()),
+        digest_weekday: cfg.digest_weekday,
+        no_word_message: cfg.no_word_message,
+        announce_reactions: Arc::new(cfg.announce_reactions),
+        decisions_path: cfg.decisions_path,
+        webhook_name: cfg.webhook_name,
+        webhook_avatar_url: cfg.webhook_avatar_url,
     };
 
     let intents = GatewayIntents::GUILDS | GatewayIntents::GUILD_MESSAGES;
 
+    let mut commands = vec![
+        suggest(),
+        history(),
+        weights(),
+        unused(),
+        help(),
+        statssummary(),
+        schedule(),
+        onday(),
+        queueclear(),
+        setweight(),
+        reroll(),
+        about(),
+        explain(),
+        backup(),
+        restore(),
+        peek(),
+        forgetuser(),
+        pregenerate(),
+        scheduled(),
+        distribution(),
+        rescore(),
+        pause(),
+        resume(),
+        suggestmany(),
+        letterdrought(),
+        health(),
+        rejections(),
+        runnow(),
+        queuecheck(),
+        replay(),
+        inspect(),
+        dictlint(),
+        reuserequests(),
+        lastscore(),
+        compare(),
+        setchannel(),
+        samplepicks(),
+        dropinvalid(),
+    ];
+    #[cfg(feature = "charts")]
+    commands.push(statschart());
+    if cfg.command_cooldown_secs > 0 {
+        let cooldown = std::time::Duration::from_secs(cfg.command_cooldown_secs);
+        for cmd in commands
+            .iter_mut()
+            .filter(|c| COOLDOWN_COMMANDS.contains(&c.name.as_str()))
+        {
+            cmd.cooldown_config
+                .get_mut()
+                .expect("cooldown config lock poisoned")
+                .user = Some(cooldown);
+        }
+    }
+
+    let register_guild = cfg.guild_id.map(GuildId::new);
     let framework = poise::Framework::<AppState, anyhow::Error>::builder()
         .options(poise::FrameworkOptions {
-            commands: vec![suggest(), history()],
+            commands,
+            on_error: |error| Box::pin(on_error(error)),
             ..Default::default()
         })
         .setup(move |ctx, _ready, framework| {
-            let state = state.clone();
             Box::pin(async move {
-                poise::builtins::register_globally(ctx, &framework.options().commands).await?;
+                let mut guilds = HashMap::new();
+                for cfg in pending_guilds {
+                    let id = match cfg.guild_id {
+                        Some(id) => GuildId::new(id),
+                        None => {
+                            guild_of_channel(ctx, ChannelId::new(cfg.announce_channel_id)).await?
+                        }
+                    };
+                    let g = GuildState::load(id, &cfg)?;
+                    if let Err(e) = verify_guild(ctx, &g).await {
+                        error!("Server {id} is misconfigured: {e:#}");
+                        return Err(e);
+                    }
+                    guilds.insert(id, Arc::new(g));
+                }
+                info!("Serving {} server(s)", guilds.len());
+                state.guilds = Arc::new(guilds);
+
+                let commands = &framework.options().commands;
+                match register_guild {
+                    // guild commands update instantly, global ones can take an hour to show up
+                    Some(id) => {
+                        poise::builtins::register_in_guild(ctx, commands, id).await?;
+                        info!("Registered commands in server {id} only");
+                    }
+                    None => poise::builtins::register_globally(ctx, commands).await?,
+                }
                 spawn_scheduler(ctx.clone(), state.clone());
                 Ok(state)
             })
@@ -87,223 +510,3370 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// The server an announce channel belongs to
+async fn guild_of_channel(
+    ctx: &serenity::all::Context,
+    channel_id: ChannelId,
+) -> anyhow::Result<GuildId> {
+    channel_id
+        .to_channel(ctx)
+        .await
+        .with_context(|| format!("looking up announce channel {channel_id}"))?
+        .guild()
+        .map(|c| c.guild_id)
+        .with_context(|| format!("announce channel {channel_id} is not in a server"))
+}
+
+/// Checks the announce channel belongs to the server and the ping role exists in it, so a wrong
+/// id fails at startup rather than every night
+async fn verify_guild(ctx: &serenity::all::Context, guild: &GuildState) -> anyhow::Result<()> {
+    let id = guild.id;
+    let channel_id = guild.announce_channel();
+    let channel_guild = guild_of_channel(ctx, channel_id).await?;
+    // every server shares the one test channel, so it can only be in one of them
+    anyhow::ensure!(
+        channel_guild == id || guild.test_channel_id.is_some(),
+        "announce channel {channel_id} is in server {channel_guild}, not {id}"
+    );
+    let Some(role_id) = guild.role_id else {
+        return Ok(());
+    };
+    let roles = id
+        .roles(&ctx.http)
+        .await
+        .with_context(|| format!("looking up roles in server {id}"))?;
+    anyhow::ensure!(
+        roles.contains_key(&role_id),
+        "role {role_id} does not exist in server {id}"
+    );
+    Ok(())
+}
+
+/// The configured server a command was used in
+fn guild_of<'a>(ctx: Ctx<'a>) -> anyhow::Result<&'a GuildState> {
+    ctx.guild_id()
+        .and_then(|id| ctx.data().guilds.get(&id))
+        .map(Arc::as_ref)
+        .context("This server isn't set up for the bot.")
+}
+
+/// Formats a sample date so a bad format string fails at startup instead of at announce time
+fn validate_date_format(fmt: &str) -> anyhow::Result<()> {
+    use std::fmt::Write;
+
+    let sample = NaiveDate::from_ymd_opt(2025, 5, 5).expect("valid sample date");
+    let mut buf = String::new();
+    write!(buf, "{}", sample.format(fmt))
+        .map_err(|_| anyhow::anyhow!("Invalid DATE_FORMAT: {fmt}"))?;
+    Ok(())
+}
+
+async fn on_error(error: poise::FrameworkError<'_, AppState, anyhow::Error>) {
+    if let poise::FrameworkError::CommandCheckFailed {
+        ctx, error: None, ..
+    } = error
+    {
+        let reply = CreateReply::default()
+            .content("You don't have permission to use this command.")
+            .ephemeral(true);
+        if let Err(e) = ctx.send(reply).await {
+            error!("Failed to send check failure reply: {:?}", e);
+        }
+        return;
+    }
+    if let Err(e) = poise::builtins::on_error(error).await {
+        error!("Error while handling error: {:?}", e);
+    }
+}
+
+/// Allows members with the configured admin role, or with Manage Server when no role is set
+async fn is_admin(ctx: Ctx<'_>) -> anyhow::Result<bool> {
+    let Some(member) = ctx.author_member().await else {
+        return Ok(false);
+    };
+    Ok(match ctx.data().admin_role_id {
+        Some(role_id) => member.roles.contains(&role_id),
+        None => member.permissions.is_some_and(|p| p.manage_guild()),
+    })
+}
+
 fn spawn_scheduler(ctx: poise::serenity_prelude::Context, state: AppState) {
     tokio::spawn(async move {
+        let mut last_run: Option<NaiveDate> = None;
         loop {
             let now_utc = chrono::Utc::now();
             let now_local = state.timezone.from_utc_datetime(&now_utc.naive_utc());
             let next_local = {
                 let mut d = now_local.date_naive();
-                // if already past 23:55 today, use tomorrow
-                let today_target = state
-                    .timezone
-                    .with_ymd_and_hms(d.year(), d.month(), d.day(), 23, 55, 0)
-                    .unwrap();
-                if now_local >= today_target {
+                let lead = state.announce_lead_days;
+                // if already past today's announce time (or already ran today), use tomorrow
+                if last_run == Some(d) || now_local >= announce_time(&state.timezone, d, lead, 0) {
                     d = d.succ_opt().unwrap();
                 }
-                state
-                    .timezone
-                    .with_ymd_and_hms(d.year(), d.month(), d.day(), 23, 55, 0)
-                    .unwrap()
+                let offset = jitter_minutes(&mut rand::rng(), state.announce_jitter_minutes);
+                last_run = Some(d);
+                announce_time(&state.timezone, d, lead, offset)
             };
-            let dur = (next_local - now_local).to_std().unwrap_or_default();
-            sleep_until(Instant::now() + dur).await;
+            // the monotonic clock can drift from the wall clock, a run at midnight that woke a
+            // moment early would still announce yesterday
+            loop {
+                let now_local = state
+                    .timezone
+                    .from_utc_datetime(&chrono::Utc::now().naive_utc());
+                match (next_local - now_local).to_std() {
+                    Ok(dur) if !dur.is_zero() => sleep_until(Instant::now() + dur).await,
+                    _ => break,
+                }
+            }
+
+            let target = state.target_date();
+            let mut failing = run_spaced(state.guilds.values(), state.announce_spacing, |guild| {
+                run_scheduled(&ctx, &state, guild)
+            })
+            .await;
 
-            if let Err(e) = run_once(&ctx, &state).await {
-                error!("scheduler error: {:?}", e);
+            // posting can fail for a moment (Discord hiccups), retry those servers shortly
+            // rather than leaving them without a word until tomorrow night
+            let mut attempt = 0;
+            while !failing.is_empty() {
+                let Some(delay) = retry_delay(attempt, &mut rand::rng()) else {
+                    error!(
+                        "Giving up on {} servers after {attempt} retries",
+                        failing.len()
+                    );
+                    break;
+                };
+                attempt += 1;
+                sleep_until(Instant::now() + delay).await;
+                if state.target_date() != target {
+                    warn!("The day moved on while retrying, not retrying {target} again");
+                    break;
+                }
+                failing = run_spaced(failing, state.announce_spacing, |guild| {
+                    run_scheduled(&ctx, &state, guild)
+                })
+                .await;
+            }
+
+            for guild in state.guilds.values() {
+                post_digest(&ctx, &state, guild).await;
             }
         }
     });
 }
 
-/// # Errors
-/// Will error if get weighted fails
-pub async fn run_once(ctx: &serenity::all::Context, state: &AppState) -> anyhow::Result<()> {
-    use chrono::{Duration, Utc};
+/// Runs `run` on each item in turn, waiting `spacing` between them, and returns the items it
+/// reported failing for
+async fn run_spaced<T: Copy, F: Future<Output = bool>>(
+    items: impl IntoIterator<Item = T>,
+    spacing: std::time::Duration,
+    mut run: impl FnMut(T) -> F,
+) -> Vec<T> {
+    let mut failing = Vec::new();
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(spacing).await;
+        }
+        if run(item).await {
+            failing.push(item);
+        }
+    }
+    failing
+}
 
-    let now_local = state.timezone.from_utc_datetime(&Utc::now().naive_utc());
-    let target = now_local.date_naive() + Duration::days(1);
+/// Posts the last week's words on `DIGEST_WEEKDAY`, once per day
+async fn post_digest(ctx: &serenity::all::Context, state: &AppState, guild: &GuildState) {
+    let Some(weekday) = state.digest_weekday else {
+        return;
+    };
+    let today = state.today();
+    if today.weekday() != weekday
+        || guild.paused.load(Ordering::Relaxed)
+        || guild.store.with(|s| s.last_digest) == Some(today)
+    {
+        return;
+    }
 
-    // 1) Reuse
-    if let Some((existing, sug)) = state.store.with(|s| {
-        s.history.iter().rev().find(|e| e.date == target).map(|e| {
-            (
-                e.word.clone(),
-                e.suggested_by.map(|user| format!("<@{user}>")),
-            )
-        })
-    }) {
-        return announce(ctx, state, target, &existing, sug.as_deref()).await;
+    let week = guild.store.with(|s| digest_week(s, today));
+    if week.is_empty() {
+        return;
     }
 
-    // 2) Queue first: drop invalid/used; pick first valid
-    let picked_from_queue: Option<(String, serenity::all::UserId)> = loop {
-        let maybe = state.store.with_mut(|s| s.queue.pop_front());
-        let Some((user_id, word)) = maybe else {
-            break None;
+    let mut text = "This week's starter words:".to_owned();
+    for (date, word, user) in week {
+        // today's puzzle is still being played
+        let word = if date == today && state.spoiler {
+            format!("||`{word}`||")
+        } else {
+            format!("`{word}`")
         };
-        let w = word.to_lowercase();
-        let is_valid = state.dictionary.contains_key(&w);
-        let is_used = state.store.with(|s| s.used.contains(&w));
-        if is_valid && !is_used {
-            state
-                .store
-                .with_mut(|s| s.mark_used(target, w.clone(), Some(user_id)));
-            break Some((w, user_id));
+        text.push_str(&format!("\n{} — {word}", date.format(&state.date_format)));
+        if let Some(user) = user {
+            let credit = credit(ctx, state, guild, user).await;
+            text.push_str(&format!(" (suggested by {credit})"));
         }
-    };
+    }
+    match ConfiguredSink::new(ctx, state, guild).digest(&text).await {
+        Ok(()) => guild.store.with_mut(|s| s.last_digest = Some(today)),
+        Err(e) => error!("Failed to post the weekly digest in {}: {:?}", guild.id, e),
+    }
+}
 
-    // 3) Fallback weighted pick
-    let (word, mention): (String, Option<String>) = if let Some((w, uid)) = picked_from_queue {
-        (w, Some(format!("<@{}>", uid.get())))
-    } else {
-        let used = state.store.with(|s| s.used.clone());
-        let Some(w) = words::pick_weighted(&state.dictionary, Some(&used), Some(SAMPLE_ALPHA))
-            .map(str::to_owned)
-        else {
-            error!("Failed to get next word");
-            return Err(anyhow::Error::msg("Failed to get next word"));
-        };
-        state
-            .store
-            .with_mut(|s| s.mark_used(target, w.clone(), None));
-        (w, None)
-    };
+/// The announced words of the seven days up to `today`, oldest first. Later dates can already
+/// be announced with `ANNOUNCE_LEAD_DAYS`, but haven't been played
+fn digest_week(s: &BotState, today: NaiveDate) -> Vec<(NaiveDate, String, Option<UserId>)> {
+    let since = today - chrono::Duration::days(6);
+    let mut week: Vec<_> = s
+        .history
+        .iter()
+        .filter(|e| e.announced && e.date >= since && e.date <= today)
+        .map(|e| (e.date, e.word.clone(), e.suggested_by))
+        .collect();
+    week.sort_unstable_by_key(|(d, _, _)| *d);
+    week
+}
 
-    announce(ctx, state, target, &word, mention.as_deref()).await
+/// Runs one server's announcement, returning whether it failed in a way worth retrying
+async fn run_scheduled(ctx: &serenity::all::Context, state: &AppState, guild: &GuildState) -> bool {
+    let sink = ConfiguredSink::new(ctx, state, guild);
+    match run_once(&sink, ctx, state, guild, state.slow_reveal).await {
+        Ok(_) => false,
+        Err(e) => {
+            error!("scheduler error in guild {}: {:?}", guild.id, e);
+            e.is_transient()
+        }
+    }
 }
 
-async fn announce(
-    ctx: &serenity::all::Context,
-    state: &AppState,
-    date: chrono::NaiveDate,
-    word: &str,
-    suggested_by: Option<&str>,
-) -> anyhow::Result<()> {
-    let mut parts: Vec<String> = Vec::new();
-    if let Some(m) = suggested_by {
-        parts.push(format!("Suggested by {m}"));
+/// How long to wait before retry number `attempt` (from 0), doubling from 15 seconds up to
+/// two minutes with ±20% jitter, or `None` once retries are used up
+fn retry_delay(attempt: u32, rng: &mut impl rand::Rng) -> Option<std::time::Duration> {
+    const MAX_RETRIES: u32 = 5;
+    const BASE_SECS: f64 = 15.0;
+    const CAP_SECS: f64 = 120.0;
+    if attempt >= MAX_RETRIES {
+        return None;
     }
+    let secs = (BASE_SECS * 2f64.powi(attempt as i32)).min(CAP_SECS);
+    Some(std::time::Duration::from_secs_f64(
+        secs * rng.random_range(0.8..=1.2),
+    ))
+}
 
-    let suffix = if parts.is_empty() {
-        String::new()
+/// The announce time on `day`: 23:55 the evening before, or midnight as the day starts when
+/// `lead_days` is 0. Shifted by `offset_minutes` but kept within that day
+fn announce_time(
+    tz: &Tz,
+    day: NaiveDate,
+    lead_days: i64,
+    offset_minutes: i64,
+) -> chrono::DateTime<Tz> {
+    let at = |h, m, s| {
+        let naive = day.and_hms_opt(h, m, s).unwrap();
+        // a DST change can skip the time, some zones jump right at midnight
+        tz.from_local_datetime(&naive)
+            .earliest()
+            .or_else(|| {
+                tz.from_local_datetime(&(naive + chrono::Duration::hours(1)))
+                    .earliest()
+            })
+            .unwrap()
+    };
+    let base = if lead_days == 0 {
+        at(0, 0, 0)
     } else {
-        parts.join("\n").to_string()
+        at(23, 55, 0)
     };
-    let msg = format!(
-        "<@&{}>\nTomorrow’s Wordle starter ({date}) is: ||`{word}`||\n{suffix}",
-        state.role_id
-    );
-    state.channel_id.say(&ctx.http, msg).await?;
-    Ok(())
+    (base + chrono::Duration::minutes(offset_minutes)).clamp(at(0, 0, 0), at(23, 59, 59))
 }
 
-#[poise::command(slash_command)]
-pub async fn suggest(
-    ctx: Ctx<'_>,
-    #[description = "5-letter word"] word: String,
-) -> anyhow::Result<()> {
-    let uid = ctx.author().id;
-    let w = word.trim().to_lowercase();
+/// A random offset in `-max..=max` minutes
+fn jitter_minutes(rng: &mut impl rand::Rng, max: i64) -> i64 {
+    if max <= 0 {
+        return 0;
+    }
+    rng.random_range(-max..=max)
+}
 
-    if w.len() != 5 || !w.chars().all(|c| c.is_ascii_lowercase()) {
-        ctx.send(
-            CreateReply::default()
-                .content("Rejected: provide a 5-letter a–z word.")
-                .ephemeral(true),
-        )
-        .await?;
-        return Ok(());
+/// # Errors
+/// Will error if no word can be selected or the announcement can't be posted
+/// With `slow_reveal` a newly picked word is shown a letter at a time
+pub async fn run_once(
+    sink: &impl AnnounceSink,
+    members: &impl MemberNames,
+    state: &AppState,
+    guild: &GuildState,
+    slow_reveal: bool,
+) -> Result<RunOutcome, SelectError> {
+    if guild.paused.load(Ordering::Relaxed) {
+        info!("Announcements are paused in {}, skipping", guild.id);
+        return Ok(RunOutcome::Paused);
     }
-    if !ctx.data().dictionary.contains_key(&w) {
-        ctx.send(
-            CreateReply::default()
-                .content("Rejected: not in dictionary.")
-                .ephemeral(true),
-        )
-        .await?;
-        return Ok(());
+    let unskipped = state.unskipped_target_date();
+    if state.skip_weekdays.contains(&unskipped.weekday()) {
+        info!(
+            "No puzzle on {unskipped} ({}), skipping",
+            unskipped.weekday()
+        );
+        return Ok(RunOutcome::SkippedDay(unskipped));
     }
-    if ctx.data().store.with(|s| s.used.contains(&w)) {
-        ctx.send(
-            CreateReply::default()
-                .content("Rejected: already used previously.")
-                .ephemeral(true),
-        )
-        .await?;
-        return Ok(());
+    let _guard = guild.run_lock.lock().await;
+    let target = state.target_date();
+    if state.dict().is_empty() {
+        post_no_word(sink, state, target).await;
+        return Err(SelectError::DictionaryEmpty);
     }
-    if ctx
-        .data()
-        .store
-        .with(|s| s.queue.iter().any(|(_, q)| q == &w))
-    {
-        ctx.send(
-            CreateReply::default()
-                .content("Already queued.")
-                .ephemeral(true),
-        )
-        .await?;
-        return Ok(());
+
+    if state.reveal {
+        let mut previous = target - chrono::Duration::days(1);
+        while state.skip_weekdays.contains(&previous.weekday()) {
+            previous -= chrono::Duration::days(1);
+        }
+        if let Err(e) = reveal(sink, guild, previous).await {
+            error!("Failed to reveal the word for {previous}: {:?}", e);
+        }
     }
 
-    ctx.data()
-        .store
-        .with_mut(|s| s.queue.push_back((uid, w.clone())));
+    // 1) Reuse
+    if let Some((existing, sug, announced, message)) = guild.store.with(|s| {
+        s.history
+            .iter()
+            .rev()
+            .find(|e| e.date == target)
+            .map(|e| (e.word.clone(), e.suggested_by, e.announced, e.message))
+    }) {
+        // with nothing to edit, announcing again would post and ping a second time
+        if announced && message.is_none() {
+            info!(
+                "{existing} was already announced for {target} with no message to edit, skipping"
+            );
+            return Ok(RunOutcome::AlreadyAnnounced(target, existing));
+        }
+        let sug = match sug {
+            Some(user) => Some(credit_with_note(members, state, guild, user, target).await),
+            None => None,
+        };
+        let posted = announce(sink, message, target, &existing, sug.as_deref()).await?;
+        guild.store.with_mut(|s| s.mark_announced(target, posted));
+        write_public_view(state, guild);
+        archive_history(state, guild, target);
+        return Ok(RunOutcome::Reused(target, existing));
+    }
 
-    ctx.send(
-        CreateReply::default()
-            .content(format!("Queued `{w}`."))
-            .ephemeral(true),
-    )
-    .await?;
-    Ok(())
+    // 2) Scheduled override, then queue, then fallback weighted pick
+    let candidates = guild.store.with(|s| state.unused_count(s, target));
+    let Some((word, source)) = select_word(state, guild, target, &mut rand::rng()) else {
+        post_no_word(sink, state, target).await;
+        return Err(SelectError::PoolExhausted);
+    };
+    log_decision(state, guild, target, &word, source, candidates);
+    let mention = match source {
+        PickSource::Queue(user) => {
+            Some(credit_with_note(members, state, guild, user, target).await)
+        }
+        PickSource::Scheduled | PickSource::Weighted => None,
+    };
+
+    let posted = if slow_reveal {
+        sink.announce_slowly(target, &word, mention.as_deref())
+            .await?
+    } else {
+        sink.announce(target, &word, mention.as_deref()).await?
+    };
+    guild.store.with_mut(|s| s.mark_announced(target, posted));
+    write_public_view(state, guild);
+    archive_history(state, guild, target);
+    warn_if_pool_low(sink, state, guild, target).await;
+    Ok(RunOutcome::Picked(target, word, source))
 }
 
-#[poise::command(slash_command)]
-pub async fn history(
-    ctx: Ctx<'_>,
-    #[description = "How many days back (default 14)"] days_back: Option<i64>,
-) -> anyhow::Result<()> {
-    let days = days_back.unwrap_or(14).clamp(1, 3650);
+/// Appends a line to `DECISIONS_PATH` recording how a day's word was chosen. Failing to write
+/// is logged and otherwise ignored, the audit trail shouldn't stop an announcement
+fn log_decision(
+    state: &AppState,
+    guild: &GuildState,
+    date: NaiveDate,
+    word: &str,
+    source: PickSource,
+    candidates: usize,
+) {
+    use std::io::Write;
 
-    // compute cutoff in the bot's configured timezone
-    let now_local = ctx
-        .data()
-        .timezone
-        .from_utc_datetime(&chrono::Utc::now().naive_utc());
-    let cutoff = now_local.date_naive() - chrono::Duration::days(days);
+    let Some(path) = &state.decisions_path else {
+        return;
+    };
+    let (source, suggested_by) = match source {
+        PickSource::Scheduled => ("scheduled", None),
+        PickSource::Queue(user) => ("queue", Some(user)),
+        PickSource::Weighted => ("weighted", None),
+    };
+    let line = serde_json::json!({
+        "timestamp": chrono::Utc::now(),
+        "guild_id": guild.id,
+        "date": date,
+        "source": source,
+        "suggested_by": suggested_by,
+        "candidates": candidates,
+        "word": word,
+    });
+    let written = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut f| writeln!(f, "{line}"));
+    if let Err(e) = written {
+        error!("Failed to append to {path}: {e}");
+    }
+}
+
+/// Moves history older than `HISTORY_KEEP_DAYS` into `{STATE_PATH}.archive.ndjson.gz`
+fn archive_history(state: &AppState, guild: &GuildState, target: NaiveDate) {
+    let Some(days) = state.history_keep_days else {
+        return;
+    };
+    let cutoff = target - chrono::Duration::days(days);
+    let archive_path = format!("{}.archive.ndjson.gz", guild.store.path());
+    match guild.store.archive_before(cutoff, &archive_path) {
+        Ok(0) => {}
+        Ok(n) => info!("Archived {n} history entries from before {cutoff} to {archive_path}"),
+        Err(e) => error!("Failed to archive history to {archive_path}: {:?}", e),
+    }
+}
+
+/// Rewrites the server's `PUBLIC_VIEW_PATH` file, if it has one, for trackers to poll
+fn write_public_view(state: &AppState, guild: &GuildState) {
+    let Some(path) = &guild.public_view_path else {
+        return;
+    };
+    // with a longer ANNOUNCE_LEAD_DAYS, announced words can be days from being played
+    let view = guild.store.public_view(state.today());
+    let written = serde_json::to_vec_pretty(&view)
+        .map_err(anyhow::Error::from)
+        .and_then(|bytes| {
+            let tmp = format!("{path}.tmp");
+            fs::write(&tmp, bytes)?;
+            fs::rename(&tmp, path)?;
+            Ok(())
+        });
+    if let Err(e) = written {
+        error!("Failed to write the public view to {path}: {:?}", e);
+    }
+}
 
-    // collect entries >= cutoff
-    let mut rows = ctx.data().store.with(|s| {
+/// `credit`, followed by the note the suggester left for `date`'s word, if any
+async fn credit_with_note(
+    members: &impl MemberNames,
+    state: &AppState,
+    guild: &GuildState,
+    user: UserId,
+    date: NaiveDate,
+) -> String {
+    let credit = credit(members, state, guild, user).await;
+    let note = guild.store.with(|s| {
         s.history
             .iter()
-            .filter(|e| e.date >= cutoff)
-            .cloned()
-            .collect::<Vec<_>>()
+            .rev()
+            .find(|e| e.date == date)
+            .and_then(|e| e.note.clone())
     });
+    match note {
+        Some(note) => format!("{credit}: {note}"),
+        None => credit,
+    }
+}
 
-    // newest first; tie-break by word
-    rows.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.word.cmp(&b.word)));
+/// How a suggester is credited in announcements: a mention, or their server nickname as plain
+/// text when `CREDIT_DISPLAY_NAMES` is set and the member can be looked up
+async fn credit(
+    members: &impl MemberNames,
+    state: &AppState,
+    guild: &GuildState,
+    user: UserId,
+) -> String {
+    if state.credit_display_names
+        && let Some(name) = members.display_name(guild, user).await
+    {
+        // announcements allow pings, so a nickname like "@everyone" must not become one
+        return name.replace('@', "@\u{200b}");
+    }
+    format!("<@{user}>")
+}
 
-    if rows.is_empty() {
-        ctx.say(format!("No entries in the last {days} days."))
-            .await?;
-        return Ok(());
+/// What a call to `run_once` did
+pub enum RunOutcome {
+    Paused,
+    /// No puzzle on this date because its weekday is skipped
+    SkippedDay(NaiveDate),
+    /// The date already had a word, which was announced again
+    Reused(NaiveDate, String),
+    /// The date's word was announced but its message wasn't kept, so it was left alone
+    AlreadyAnnounced(NaiveDate, String),
+    Picked(NaiveDate, String, PickSource),
+}
+
+/// Picks and records the word for `target`: the scheduled word, then the queue, then a weighted
+/// pick. Returns the word and who suggested it
+fn select_word(
+    state: &AppState,
+    guild: &GuildState,
+    target: NaiveDate,
+    rng: &mut impl rand::Rng,
+) -> Option<(String, PickSource)> {
+    if let Some(w) = take_scheduled(state, guild, target) {
+        return Some((w, PickSource::Scheduled));
     }
+    if let Some((w, uid)) = take_from_queue(state, guild, target) {
+        return Some((w, PickSource::Queue(uid)));
+    }
+    let used = guild.store.with(|s| state.excluded(s, target));
+    let w = pick_fallback(state, guild, target, &used, rng)?;
+    guild.store.with_mut(|s| {
+        if s.mark_used(target, w.clone(), PickSource::Weighted) {
+            return Some((w, PickSource::Weighted));
+        }
+        // someone else recorded a word since `used` was read, that one is the day's word
+        let e = s.history.iter().rev().find(|e| e.date == target)?;
+        warn!(
+            "{target} already had `{}` recorded, not recording `{w}`",
+            e.word
+        );
+        Some((e.word.clone(), e.source.unwrap_or(PickSource::Weighted)))
+    })
+}
 
-    // build a message under ~1900 chars
-    let mut out = String::with_capacity(1024);
-    out.push_str(format!("Previous starting words for the last {days} days\n").as_str());
-    for e in rows {
-        let line = format!("{} — `{}`\n", e.date, e.word);
-        if out.len() + line.len() > 1900 {
-            break;
+/// Takes and marks used the word scheduled for `target`, if it's still in the dictionary
+fn take_scheduled(state: &AppState, guild: &GuildState, target: NaiveDate) -> Option<String> {
+    guild.store.with_mut(|s| {
+        let w = s.scheduled.get(&target)?.clone();
+        if !state.dict().contains_key(&w) {
+            warn!("Scheduled word `{w}` for {target} is not in the dictionary, skipping");
+            s.scheduled.remove(&target);
+            return None;
         }
-        out.push_str(&line);
-    }
+        if !s.mark_used(target, w.clone(), PickSource::Scheduled) {
+            return None;
+        }
+        s.scheduled.remove(&target);
+        Some(w)
+    })
+}
 
-    ctx.say(out).await?;
-    Ok(())
+/// Takes and marks used the first valid queued word, dropping invalid/used entries and
+/// skipping suggesters on cooldown
+fn take_from_queue(
+    state: &AppState,
+    guild: &GuildState,
+    target: NaiveDate,
+) -> Option<(String, UserId)> {
+    let dict = state.dict();
+    guild.store.with_mut(|s| {
+        let excluded = state.excluded(s, target);
+        s.queue
+            .retain(|q| queued_word_problem(&dict, &excluded, &q.word.to_lowercase()).is_none());
+        let order = queue_order(state, &s.queue);
+        let pos = order.iter().position(|&i| {
+            !s.suggester_on_cooldown(s.queue[i].user, target, state.suggester_cooldown_days)
+        })?;
+        let QueuedWord {
+            user, word, note, ..
+        } = s.queue[order[pos]].clone();
+        let w = word.to_lowercase();
+        if !s.mark_used(target, w.clone(), PickSource::Queue(user)) {
+            return None;
+        }
+        if let Some(e) = s.history.last_mut() {
+            e.note = note;
+        }
+        for &i in &order[..pos] {
+            s.queue[i].skipped += 1;
+        }
+        s.queue.remove(order[pos]);
+        Some((w, user))
+    })
+}
+
+/// Queue indices in the order they're considered: first come first served, or with
+/// `QUEUE_AGING` the entries passed over most often first
+fn queue_order(state: &AppState, queue: &std::collections::VecDeque<QueuedWord>) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..queue.len()).collect();
+    if state.queue_aging {
+        // stable, so ties keep their queue order
+        order.sort_by_key(|&i| std::cmp::Reverse(queue[i].skipped));
+    }
+    order
+}
+
+/// Why `run_once` would drop a queued word, if it would
+fn queued_word_problem(
+    dict: &HashMap<String, f64>,
+    excluded: &HashSet<String>,
+    word: &str,
+) -> Option<&'static str> {
+    if !dict.contains_key(word) {
+        Some("not in dictionary")
+    } else if excluded.contains(word) {
+        Some("already used")
+    } else {
+        None
+    }
+}
+
+/// Weighted pick outside of `excluded`, reusing the oldest word if nothing is left. On
+/// `SURPRISE_WEEKDAYS` every unused word has the same odds
+fn pick_fallback(
+    state: &AppState,
+    guild: &GuildState,
+    date: NaiveDate,
+    excluded: &HashSet<String>,
+    rng: &mut impl rand::Rng,
+) -> Option<String> {
+    guild
+        .store
+        .with(|s| pick_from_state(state, s, date, excluded, rng))
+}
+
+/// `pick_fallback` against any state, such as history rolled back for `/replay`
+fn pick_from_state(
+    state: &AppState,
+    s: &BotState,
+    date: NaiveDate,
+    excluded: &HashSet<String>,
+    rng: &mut impl rand::Rng,
+) -> Option<String> {
+    if state.surprise_weekdays.contains(&date.weekday()) {
+        return words::pick_uniform(&state.dict(), Some(excluded), rng)
+            .map(str::to_owned)
+            .or_else(|| reuse_oldest(state, s));
+    }
+    let recent = s.recent_words(state.recent_letter_window);
+    let recent_rare = words::rare_letters(recent.iter().map(String::as_str));
+    let multiplier =
+        |w: &str| words::recent_letter_multiplier(w, &recent_rare, state.recent_letter_penalty);
+    words::pick_weighted(
+        &state.dict(),
+        Some(excluded),
+        Some(SAMPLE_ALPHA),
+        state.easy_mode,
+        multiplier,
+        rng,
+    )
+    .map(str::to_owned)
+    .or_else(|| reuse_oldest(state, s))
+}
+
+/// Falls back to the least recently used word once the pool is exhausted, if enabled
+fn reuse_oldest(state: &AppState, s: &BotState) -> Option<String> {
+    if !state.reuse_oldest_when_exhausted {
+        return None;
+    }
+    let dict = state.dict();
+    let word = s.least_recently_used(|w| dict.contains_key(w))?;
+    warn!("Word pool exhausted, reusing least recently used word");
+    Some(word)
+}
+
+async fn warn_if_pool_low(
+    sink: &impl AnnounceSink,
+    state: &AppState,
+    guild: &GuildState,
+    target: NaiveDate,
+) {
+    let remaining = guild.store.with(|s| state.unused_count(s, target));
+    if remaining >= state.low_pool_threshold {
+        return;
+    }
+    warn!("Only {remaining} unused words remain");
+    let msg = format!("Heads up: only {remaining} unused starter words remain in the dictionary.");
+    if let Err(e) = sink.mod_notice(&msg).await {
+        error!("Failed to post low pool warning: {:?}", e);
+    }
+}
+
+/// Posts the announcement for `date` through `sink`, or edits `existing` if it was already
+/// posted so the role isn't pinged twice
+async fn announce(
+    sink: &impl AnnounceSink,
+    existing: Option<PostedMessage>,
+    date: chrono::NaiveDate,
+    word: &str,
+    suggested_by: Option<&str>,
+) -> Result<Option<PostedMessage>, SelectError> {
+    if let Some(message) = existing {
+        match sink.edit(message, date, word, suggested_by).await {
+            Ok(()) => return Ok(Some(message)),
+            Err(e) => warn!("Couldn't edit the announcement for {date}, posting again: {e}"),
+        }
+    }
+    sink.announce(date, word, suggested_by).await
+}
+
+/// Posts `NO_WORD_MESSAGE`, if set, so a day without a word isn't just silent
+async fn post_no_word(sink: &impl AnnounceSink, state: &AppState, date: NaiveDate) {
+    let Some(message) = &state.no_word_message else {
+        return;
+    };
+    if let Err(e) = sink.no_word(date, message).await {
+        error!("Failed to post the no-word message for {date}: {:?}", e);
+    }
+}
+
+/// Posts the word for `date` without spoilers, once, if it was announced
+async fn reveal(
+    sink: &impl AnnounceSink,
+    guild: &GuildState,
+    date: NaiveDate,
+) -> Result<(), SelectError> {
+    let Some(word) = guild.store.with(|s| {
+        s.history
+            .iter()
+            .rev()
+            .find(|e| e.date == date && e.announced && !e.revealed)
+            .map(|e| e.word.clone())
+    }) else {
+        return Ok(());
+    };
+    sink.reveal(date, &word).await?;
+    guild.store.with_mut(|s| {
+        if let Some(e) = s.history.iter_mut().rev().find(|e| e.date == date) {
+            e.revealed = true;
+        }
+    });
+    Ok(())
+}
+
+/// Parses a date argument given as YYYY-MM-DD
+fn parse_date_arg(input: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d").map_err(|_| {
+        format!(
+            "Couldn't read `{}` as a date, use YYYY-MM-DD.",
+            input.trim()
+        )
+    })
+}
+
+/// Discord rejects messages longer than this many characters
+const MESSAGE_CHAR_LIMIT: usize = 2000;
+
+/// Packs `header` followed by `lines` into as few messages as fit under Discord's limit
+fn split_messages(header: String, lines: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut out = header;
+    let mut out_chars = out.chars().count();
+    for line in lines {
+        let line_chars = line.chars().count();
+        if out_chars + line_chars > MESSAGE_CHAR_LIMIT && out_chars > 0 {
+            messages.push(std::mem::take(&mut out));
+            out_chars = 0;
+        }
+        out.push_str(&line);
+        out_chars += line_chars;
+    }
+    if out_chars > 0 {
+        messages.push(out);
+    }
+    messages
+}
+
+/// Suggest a word to use as a future starter
+#[poise::command(slash_command, guild_only)]
+pub async fn suggest(
+    ctx: Ctx<'_>,
+    #[description = "5-letter word"]
+    #[autocomplete = "autocomplete_word"]
+    word: String,
+    #[description = "Short note shown with your credit, e.g. \"for my birthday\""]
+    #[max_length = 100]
+    note: Option<String>,
+) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let uid = ctx.author().id;
+    let note = note.as_deref().map(sanitize_note).filter(|n| !n.is_empty());
+    let state = ctx.data();
+    let target = state.target_date();
+
+    let key = word.trim().to_lowercase();
+    if let Some(reason) = guild.recent_rejection(uid, &key, state.rejection_memory) {
+        let msg = format!("You already tried this recently. {reason}");
+        ctx.send(
+            CreateReply::default()
+                .content(msg)
+                .ephemeral(true)
+                .allowed_mentions(serenity::all::CreateAllowedMentions::new()),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let checked = validate_word(state, guild, &word);
+    // check and insert under one lock so two people can't queue the same word at once
+    let queued = guild.store.with_mut(|s| {
+        let queued = checked
+            .and_then(|w| queue_word(state, s, target, uid, &w, note.as_deref()).map(|()| w));
+        if let Err(r) = &queued {
+            *r.counter(&mut s.rejections) += 1;
+        }
+        queued
+    });
+
+    let msg = match queued {
+        Ok(w) => format!("Queued `{w}`."),
+        Err(r) => {
+            let msg = r.to_string();
+            if !state.rejection_memory.is_zero() {
+                guild.remember_rejection(uid, &key, msg.clone(), state.rejection_memory);
+            }
+            msg
+        }
+    };
+    let audited = match &note {
+        Some(n) => format!("{msg} Note: {n}"),
+        None => msg.clone(),
+    };
+    audit_suggestion(ctx, guild, &word, &audited).await;
+    ctx.send(
+        CreateReply::default()
+            .content(msg)
+            .ephemeral(true)
+            .allowed_mentions(serenity::all::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Suggest several words at once
+#[poise::command(slash_command, guild_only)]
+pub async fn suggestmany(
+    ctx: Ctx<'_>,
+    #[description = "Up to 10 words, separated by spaces or commas"] words: String,
+) -> anyhow::Result<()> {
+    const MAX_BATCH: usize = 10;
+
+    let guild = guild_of(ctx)?;
+    let uid = ctx.author().id;
+    let state = ctx.data();
+    let target = state.target_date();
+
+    let inputs: Vec<&str> = words
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty())
+        .collect();
+    if inputs.is_empty() || inputs.len() > MAX_BATCH {
+        ctx.send(
+            CreateReply::default()
+                .content(format!("Provide between 1 and {MAX_BATCH} words."))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let mut outcomes: Vec<Result<String, Rejection>> = inputs
+        .iter()
+        .map(|input| validate_word(state, guild, input))
+        .collect();
+    // one lock for the whole batch, so the queue cap and duplicates are checked as it grows
+    guild.store.with_mut(|s| {
+        for outcome in &mut outcomes {
+            if let Ok(w) = outcome
+                && let Err(r) = queue_word(state, s, target, uid, w, None)
+            {
+                *outcome = Err(r);
+            }
+            if let Err(r) = outcome {
+                *r.counter(&mut s.rejections) += 1;
+            }
+        }
+    });
+
+    let mut lines = Vec::new();
+    for (input, outcome) in inputs.iter().zip(outcomes) {
+        let msg = match outcome {
+            Ok(w) => format!("Queued `{w}`."),
+            Err(r) => r.to_string(),
+        };
+        audit_suggestion(ctx, guild, input, &msg).await;
+        lines.push(format!("`{}`: {msg}\n", input.replace('`', "'")));
+    }
+    for msg in split_messages(String::new(), lines) {
+        ctx.send(
+            CreateReply::default()
+                .content(msg)
+                .ephemeral(true)
+                .allowed_mentions(serenity::all::CreateAllowedMentions::new()),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Why a suggestion was turned down
+enum Rejection {
+    /// Holds the word as it reads once cleaned up
+    LookAlike(String),
+    BadLetters,
+    /// Holds close dictionary words to suggest instead
+    NotInDictionary(Vec<String>),
+    /// Holds the date the word can be picked again, when the request to have it back was noted
+    AlreadyUsed(Option<NaiveDate>),
+    AlreadyQueued {
+        by: UserId,
+        by_you: bool,
+        position: usize,
+        len: usize,
+    },
+    QueueCap(usize),
+}
+
+impl Rejection {
+    /// The counter in `RejectionCounts` this reason is tallied under
+    fn counter<'a>(&self, counts: &'a mut RejectionCounts) -> &'a mut u64 {
+        match self {
+            Self::LookAlike(_) => &mut counts.lookalike,
+            Self::BadLetters => &mut counts.bad_letters,
+            Self::NotInDictionary(_) => &mut counts.not_in_dictionary,
+            Self::AlreadyUsed(_) => &mut counts.already_used,
+            Self::AlreadyQueued { .. } => &mut counts.already_queued,
+            Self::QueueCap(_) => &mut counts.queue_cap,
+        }
+    }
+}
+
+impl std::fmt::Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LookAlike(w) => write!(
+                f,
+                "Rejected: your word contained hidden or look-alike characters. \
+                 If you meant `{w}`, type it in plain a–z letters."
+            ),
+            Self::BadLetters => write!(
+                f,
+                "Rejected: provide a 5-letter word using only the game's letters."
+            ),
+            Self::NotInDictionary(close) if close.is_empty() => {
+                write!(f, "Rejected: not in dictionary.")
+            }
+            Self::NotInDictionary(close) => {
+                let list = close
+                    .iter()
+                    .map(|c| format!("`{c}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "Rejected: not in dictionary. Did you mean {list}?")
+            }
+            Self::AlreadyUsed(None) => write!(f, "Rejected: already used previously."),
+            Self::AlreadyUsed(Some(eligible)) => write!(
+                f,
+                "Rejected: already used previously. Noted that you'd like it back, it can be \
+                 picked again from {eligible}."
+            ),
+            // queue entries don't carry a timestamp, so position is all there is to report
+            // names the suggester as a mention, replies showing this must suppress pings
+            Self::AlreadyQueued {
+                by,
+                by_you,
+                position,
+                len,
+            } => {
+                let by = if *by_you {
+                    "you".to_owned()
+                } else {
+                    format!("<@{by}>")
+                };
+                write!(
+                    f,
+                    "Already queued by {by}, at position {position} of {len}."
+                )
+            }
+            Self::QueueCap(cap) => write!(f, "Rejected: you already have {cap} words queued."),
+        }
+    }
+}
+
+/// Dictionary words starting with what's been typed so far, as many as Discord will show
+async fn autocomplete_word(ctx: Ctx<'_>, partial: &str) -> Vec<String> {
+    const MAX_CHOICES: usize = 25;
+    let prefix = words::normalize(partial, ctx.data().dict_options.fold_accents);
+    let mut matches: Vec<String> = ctx
+        .data()
+        .dict()
+        .keys()
+        .filter(|w| w.starts_with(&prefix))
+        .cloned()
+        .collect();
+    matches.sort_unstable();
+    matches.truncate(MAX_CHOICES);
+    matches
+}
+
+/// Checks a suggestion that doesn't depend on the queue: clean input, valid letters and in the
+/// dictionary. Returns the normalised word
+fn validate_word(state: &AppState, guild: &GuildState, input: &str) -> Result<String, Rejection> {
+    let opts = &state.dict_options;
+    let (cleaned, changed) = words::strip_lookalikes(input, opts);
+    let w = words::normalize(&cleaned, opts.fold_accents);
+
+    if changed {
+        return Err(Rejection::LookAlike(w));
+    }
+    if !opts.is_valid_word(&w) {
+        return Err(Rejection::BadLetters);
+    }
+    let dict = state.dict();
+    if !dict.contains_key(&w) {
+        let target = state.target_date();
+        let excluded = guild.store.with(|s| state.excluded(s, target));
+        let candidates = dict
+            .keys()
+            .map(String::as_str)
+            .filter(|c| !excluded.contains(*c));
+        let close = words::closest_words(candidates, &w, 2, 3);
+        return Err(Rejection::NotInDictionary(
+            close.into_iter().map(str::to_owned).collect(),
+        ));
+    }
+    Ok(w)
+}
+
+/// Queues a validated word for `user`, unless it's been used, is already queued or the user
+/// is at `QUEUE_CAP_PER_USER`
+fn queue_word(
+    state: &AppState,
+    s: &mut BotState,
+    target: NaiveDate,
+    user: UserId,
+    w: &str,
+    note: Option<&str>,
+) -> Result<(), Rejection> {
+    if state.excluded(s, target).contains(w) {
+        let eligible = match state.reuse_after_days {
+            Some(days) if state.reuse_requests => s.request_reuse(user, w, target, days),
+            _ => None,
+        };
+        return Err(Rejection::AlreadyUsed(eligible));
+    }
+    if let Some(pos) = s.queue.iter().position(|q| q.word == w) {
+        return Err(Rejection::AlreadyQueued {
+            by: s.queue[pos].user,
+            by_you: s.queue[pos].user == user,
+            position: pos + 1,
+            len: s.queue.len(),
+        });
+    }
+    let cap = state.queue_cap_per_user;
+    if cap > 0 && s.queue.iter().filter(|q| q.user == user).count() >= cap {
+        return Err(Rejection::QueueCap(cap));
+    }
+    s.queue.push_back(QueuedWord {
+        user,
+        word: w.to_owned(),
+        note: note.map(str::to_owned),
+        skipped: 0,
+    });
+    Ok(())
+}
+
+/// List used words members have asked to have back, by when they can be picked again
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn reuserequests(
+    ctx: Ctx<'_>,
+    #[description = "Clear the list after showing it"] clear: Option<bool>,
+) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let requests = if clear.unwrap_or(false) {
+        guild
+            .store
+            .with_mut(|s| std::mem::take(&mut s.reuse_requests))
+    } else {
+        guild.store.with(|s| s.reuse_requests.clone())
+    };
+    if requests.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .content("No one has asked for a used word back.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let mut by_word: HashMap<&str, (NaiveDate, Vec<UserId>)> = HashMap::new();
+    for r in &requests {
+        let entry = by_word
+            .entry(r.word.as_str())
+            .or_insert((r.eligible, Vec::new()));
+        entry.1.push(r.user);
+    }
+    let mut rows: Vec<_> = by_word.into_iter().collect();
+    rows.sort_unstable_by_key(|(w, (eligible, _))| (*eligible, *w));
+    let lines = rows.into_iter().map(|(w, (eligible, users))| {
+        let users = users
+            .iter()
+            .map(|u| format!("<@{u}>"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("`{w}` — eligible from {eligible}, asked by {users}\n")
+    });
+    let header = if clear.unwrap_or(false) {
+        "Requests to reuse words (now cleared):\n"
+    } else {
+        "Requests to reuse words:\n"
+    };
+    for msg in split_messages(header.to_owned(), lines) {
+        ctx.send(CreateReply::default().content(msg).ephemeral(true))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Makes a suggestion note safe to post: one line, at most `MAX_NOTE_CHARS`, with markdown
+/// escaped and mentions broken up
+fn sanitize_note(note: &str) -> String {
+    const MAX_NOTE_CHARS: usize = 100;
+    let mut out = String::new();
+    for c in note
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .take(MAX_NOTE_CHARS)
+    {
+        match c {
+            '*' | '_' | '~' | '`' | '|' | '>' | '#' | '[' | ']' | '\\' | '<' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '@' => out.push_str("@\u{200b}"),
+            _ => out.push(c),
+        }
+    }
+    out.trim().to_owned()
+}
+
+/// Logs a suggestion and the reply it got to the server's audit channel, if one is set
+async fn audit_suggestion(ctx: Ctx<'_>, guild: &GuildState, word: &str, outcome: &str) {
+    let Some(channel_id) = guild.audit_channel_id else {
+        return;
+    };
+    let msg = format!(
+        "<@{}> suggested `{}` — {outcome}",
+        ctx.author().id,
+        word.replace('`', "'")
+    );
+    let message = serenity::all::CreateMessage::new()
+        .content(msg)
+        .allowed_mentions(serenity::all::CreateAllowedMentions::new());
+    if let Err(e) = channel_id.send_message(ctx, message).await {
+        error!("Failed to post suggestion to audit channel: {:?}", e);
+    }
+}
+
+/// Show previous starting words
+#[poise::command(slash_command, guild_only)]
+pub async fn history(
+    ctx: Ctx<'_>,
+    #[description = "How many days back (default 14)"] days_back: Option<i64>,
+) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let days = days_back.unwrap_or(14).clamp(1, 3650);
+
+    // compute cutoff in the bot's configured timezone
+    let now_local = ctx
+        .data()
+        .timezone
+        .from_utc_datetime(&chrono::Utc::now().naive_utc());
+    let cutoff = now_local.date_naive() - chrono::Duration::days(days);
+    let target = ctx.data().target_date();
+
+    // collect announced entries >= cutoff, leaving out the upcoming word and any picked ahead
+    let mut rows = guild.store.with(|s| {
+        s.history
+            .iter()
+            .filter(|e| e.announced && e.date >= cutoff && e.date < target)
+            .cloned()
+            .collect::<Vec<_>>()
+    });
+
+    // newest first; tie-break by word
+    rows.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.word.cmp(&b.word)));
+
+    if rows.is_empty() {
+        ctx.say(format!("No entries in the last {days} days."))
+            .await?;
+        return Ok(());
+    }
+
+    let lines = rows.iter().map(|e| format!("{} — `{}`\n", e.date, e.word));
+    let header = format!("Previous starting words for the last {days} days\n");
+    for msg in split_messages(header, lines) {
+        ctx.say(msg).await?;
+    }
+    Ok(())
+}
+
+/// Show the currently loaded scoring weights
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn weights(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    let msg = format!(
+        "Current scoring weights:\n```\n{:#?}\n```",
+        ctx.data().current_weights()
+    );
+    ctx.send(CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Count the words that haven't been used yet
+#[poise::command(slash_command, guild_only)]
+pub async fn unused(
+    ctx: Ctx<'_>,
+    #[description = "How many random unused words to show (max 20)"] sample: Option<usize>,
+) -> anyhow::Result<()> {
+    use rand::seq::IteratorRandom;
+
+    let guild = guild_of(ctx)?;
+    let target = ctx.data().target_date();
+    let excluded = guild.store.with(|s| ctx.data().excluded(s, target));
+    let dict = ctx.data().dict();
+    let remaining: Vec<&str> = dict
+        .keys()
+        .filter(|w| !excluded.contains(*w))
+        .map(String::as_str)
+        .collect();
+
+    let mut out = format!(
+        "{} of {} words are still unused.",
+        remaining.len(),
+        dict.len()
+    );
+    let n = sample.unwrap_or(0).min(20);
+    if n > 0 {
+        let mut picks = remaining.into_iter().choose_multiple(&mut rand::rng(), n);
+        picks.sort_unstable();
+        let list = picks
+            .iter()
+            .map(|w| format!("`{w}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        out.push_str(&format!("\nSome of them: {list}"));
+    }
+
+    ctx.say(out).await?;
+    Ok(())
+}
+
+/// List the bot's commands
+#[poise::command(slash_command)]
+pub async fn help(
+    ctx: Ctx<'_>,
+    #[description = "Command to show details for"] command: Option<String>,
+) -> anyhow::Result<()> {
+    let admin = is_admin(ctx).await?;
+    let visible = ctx
+        .framework()
+        .options()
+        .commands
+        .iter()
+        .filter(|c| admin || c.checks.is_empty());
+
+    let mut lines = Vec::new();
+    let header = if let Some(name) = command {
+        let name = name.trim().trim_start_matches('/');
+        let Some(cmd) = visible.clone().find(|c| c.name == name) else {
+            ctx.send(
+                CreateReply::default()
+                    .content(format!("Unknown command `{name}`."))
+                    .ephemeral(true),
+            )
+            .await?;
+            return Ok(());
+        };
+        for p in &cmd.parameters {
+            let optional = if p.required { "" } else { " (optional)" };
+            lines.push(format!(
+                "• `{}`{optional}: {}\n",
+                p.name,
+                p.description.as_deref().unwrap_or("")
+            ));
+        }
+        if !cmd.checks.is_empty() {
+            lines.push("Admin only.\n".to_owned());
+        }
+        format!(
+            "`/{}` — {}\n",
+            cmd.name,
+            cmd.description.as_deref().unwrap_or("")
+        )
+    } else {
+        for cmd in visible {
+            let admin_note = if cmd.checks.is_empty() {
+                ""
+            } else {
+                " (admin)"
+            };
+            lines.push(format!(
+                "`/{}` — {}{admin_note}\n",
+                cmd.name,
+                cmd.description.as_deref().unwrap_or("")
+            ));
+        }
+        "Available commands:\n".to_owned()
+    };
+
+    // the full admin listing is well over one message
+    for msg in split_messages(header, lines) {
+        ctx.send(CreateReply::default().content(msg).ephemeral(true))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Summarise the bot's announcement history
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn statssummary(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    // words picked ahead by /pregenerate haven't been announced yet
+    let history: Vec<_> = guild
+        .store
+        .with(|s| s.history.iter().filter(|e| e.announced).cloned().collect());
+    if history.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .content("No words have been announced yet.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let total = history.len();
+    let suggested = history.iter().filter(|e| e.suggested_by.is_some()).count();
+    let dict = ctx.data().dict();
+    let scores: Vec<f64> = history
+        .iter()
+        .filter_map(|e| dict.get(&e.word).copied())
+        .collect();
+    let avg_score = if scores.is_empty() {
+        0.0
+    } else {
+        scores.iter().sum::<f64>() / scores.len() as f64
+    };
+    let first = history.iter().map(|e| e.date).min().unwrap_or_default();
+    let last = history.iter().map(|e| e.date).max().unwrap_or_default();
+
+    let msg = format!(
+        "Words announced: {total}\n\
+         From suggestions: {suggested}\n\
+         Auto-picked: {}\n\
+         Average score: {avg_score:.3}\n\
+         Date range: {first} to {last}",
+        total - suggested
+    );
+    ctx.send(CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Schedule a specific word for a future date
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn schedule(
+    ctx: Ctx<'_>,
+    #[description = "Date to use the word on (YYYY-MM-DD)"] date: String,
+    #[description = "5-letter word"]
+    #[autocomplete = "autocomplete_word"]
+    word: String,
+) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let reply = |msg: String| ctx.send(CreateReply::default().content(msg).ephemeral(true));
+
+    let date = match parse_date_arg(&date) {
+        Ok(d) => d,
+        Err(msg) => {
+            reply(msg).await?;
+            return Ok(());
+        }
+    };
+    let state = ctx.data();
+    let w = words::normalize(&word, state.dict_options.fold_accents);
+    let scheduled: Result<Option<String>, String> = guild.store.with_mut(|s| {
+        schedule_problem(state, s, date, &w)?;
+        Ok(s.scheduled.insert(date, w.clone()))
+    });
+    let msg = match scheduled {
+        Ok(Some(old)) => format!("Scheduled `{w}` for {date} (replacing `{old}`)."),
+        Ok(None) => format!("Scheduled `{w}` for {date}."),
+        Err(problem) => format!("Rejected: {problem}."),
+    };
+    reply(msg).await?;
+    Ok(())
+}
+
+/// Why `word` can't be scheduled for `date`. A date that already has a word, even one picked
+/// ahead and not yet announced, would never get to the schedule
+fn schedule_problem(
+    state: &AppState,
+    s: &BotState,
+    date: NaiveDate,
+    word: &str,
+) -> Result<(), String> {
+    if date < state.target_date() {
+        return Err(format!("{date} has already been announced"));
+    }
+    if state.skip_weekdays.contains(&date.weekday()) {
+        return Err(format!(
+            "{date} is a {}, which has no puzzle",
+            date.format("%A")
+        ));
+    }
+    if let Some(e) = s.history.iter().find(|e| e.date == date) {
+        return Err(format!("{date} already has `{}` picked", e.word));
+    }
+    if !state.dict().contains_key(word) {
+        return Err("not in dictionary".to_owned());
+    }
+    if state.excluded(s, date).contains(word) {
+        return Err("already used".to_owned());
+    }
+    Ok(())
+}
+
+/// The scores of `dict` in ascending order, for ranking a word without a full scan
+fn sorted_scores(dict: &HashMap<String, f64>) -> Vec<f64> {
+    let mut scores: Vec<f64> = dict.values().copied().collect();
+    scores.sort_unstable_by(f64::total_cmp);
+    scores
+}
+
+/// Show how unusual the latest revealed word was compared with the whole dictionary
+#[poise::command(slash_command, guild_only)]
+pub async fn lastscore(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let state = ctx.data();
+    let target = state.target_date();
+    // the word for `target` may already be announced behind spoilers, so it's left out
+    let latest = guild.store.with(|s| {
+        s.history
+            .iter()
+            .filter(|e| e.announced && e.date < target)
+            .max_by_key(|e| e.date)
+            .map(|e| (e.date, e.word.clone()))
+    });
+    let dict = state.dict();
+    let msg = match latest {
+        None => "No word has been revealed yet.".to_owned(),
+        Some((date, word)) => match dict.get(&word) {
+            None => format!("{date} — `{word}` is no longer in the dictionary."),
+            Some(&score) => {
+                let below = state.words_below(score);
+                let percentile = below as f64 * 100.0 / dict.len() as f64;
+                format!(
+                    "{date} — `{word}` scored {score:.3}, higher than {percentile:.1}% of the \
+                     {} dictionary words.",
+                    dict.len()
+                )
+            }
+        },
+    };
+    ctx.send(CreateReply::default().content(msg)).await?;
+    Ok(())
+}
+
+/// Show the word used on a specific date
+#[poise::command(slash_command, guild_only)]
+pub async fn onday(
+    ctx: Ctx<'_>,
+    #[description = "Date to look up (YYYY-MM-DD)"] date: String,
+) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let date = match parse_date_arg(&date) {
+        Ok(d) => d,
+        Err(msg) => {
+            ctx.send(CreateReply::default().content(msg).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+    let entry = guild
+        .store
+        .with(|s| s.history.iter().rev().find(|e| e.date == date).cloned());
+
+    let msg = match entry {
+        Some(e) if e.date >= ctx.data().target_date() => {
+            format!("The word for {date} hasn't been revealed yet.")
+        }
+        Some(e) => match e.suggested_by {
+            Some(user) => format!("{date} — `{}` (suggested by <@{user}>)", e.word),
+            None => format!("{date} — `{}`", e.word),
+        },
+        None => format!("No word recorded for {date}."),
+    };
+    ctx.send(
+        CreateReply::default()
+            .content(msg)
+            .allowed_mentions(serenity::all::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Remove every word from the suggestion queue
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn queueclear(
+    ctx: Ctx<'_>,
+    #[description = "Set to true to confirm clearing the queue"] confirm: bool,
+) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let msg = if confirm {
+        let removed = guild.store.with_mut(|s| {
+            let n = s.queue.len();
+            s.queue.clear();
+            n
+        });
+        info!(
+            "{} cleared the queue ({removed} entries)",
+            ctx.author().name
+        );
+        format!("Cleared {removed} queued words.")
+    } else {
+        "Queue not cleared, run again with `confirm: True` to clear it.".to_owned()
+    };
+    ctx.send(CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Change one scoring weight and rescore the dictionary
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn setweight(
+    ctx: Ctx<'_>,
+    #[description = "Weight name, as shown by /weights"] name: String,
+    #[description = "New value"] value: f64,
+    #[description = "Also write the weights to the weights file"] persist: Option<bool>,
+) -> anyhow::Result<()> {
+    let reply = |msg: String| ctx.send(CreateReply::default().content(msg).ephemeral(true));
+
+    let name = name.trim();
+    if !value.is_finite() {
+        reply("Rejected: value must be a finite number.".to_owned()).await?;
+        return Ok(());
+    }
+    let old = ctx
+        .data()
+        .weights
+        .write()
+        .field_mut(name)
+        .map(|field| std::mem::replace(field, value));
+    let Some(old) = old else {
+        reply(format!(
+            "Unknown weight `{name}`. Known weights: {}",
+            Weights::NAMES.join(", ")
+        ))
+        .await?;
+        return Ok(());
+    };
+    info!(
+        "{} set weight {name} from {old} to {value}",
+        ctx.author().name
+    );
+    ctx.data().rescore();
+
+    let mut msg = format!("Set `{name}` from {old} to {value} and rescored the dictionary.");
+    if persist.unwrap_or(false) {
+        match &ctx.data().weights_path {
+            Some(path) => {
+                ctx.data().current_weights().save(path)?;
+                msg.push_str(&format!("\nSaved to `{path}`."));
+            }
+            None => msg.push_str("\nNot saved, no WEIGHTS_PATH is configured."),
+        }
+    }
+    reply(msg).await?;
+    Ok(())
+}
+
+/// Replace an auto-picked word that hasn't been announced yet
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn reroll(
+    ctx: Ctx<'_>,
+    #[description = "Date to re-pick (YYYY-MM-DD)"] date: String,
+) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let reply = |msg: String| ctx.send(CreateReply::default().content(msg).ephemeral(true));
+
+    let date = match parse_date_arg(&date) {
+        Ok(d) => d,
+        Err(msg) => {
+            reply(msg).await?;
+            return Ok(());
+        }
+    };
+
+    let state = ctx.data();
+    let _guard = guild.run_lock.lock().await;
+    let pending = guild.store.with(|s| {
+        // the pending word is still in history, so it's excluded from the new pick
+        reroll_candidate(s, date).map(|old| (old, state.excluded(s, date)))
+    });
+    let outcome = pending.and_then(|(old, excluded)| {
+        match pick_fallback(state, guild, date, &excluded, &mut rand::rng()) {
+            Some(new) if new != old => Ok((old, new)),
+            _ => Err("No other word is available.".to_owned()),
+        }
+    });
+    if let Ok((old, new)) = &outcome {
+        guild.store.with_mut(|s| {
+            s.history
+                .retain(|e| !(e.date == date && &e.word == old && !e.announced));
+            s.mark_used(date, new.clone(), PickSource::Weighted);
+        });
+    }
+
+    let msg = match outcome {
+        Ok((old, new)) => {
+            info!("{} rerolled {date} from {old} to {new}", ctx.author().name);
+            format!("Re-picked {date}: `{old}` → `{new}`.")
+        }
+        Err(msg) => msg,
+    };
+    reply(msg).await?;
+    Ok(())
+}
+
+/// The word `/reroll` would replace on `date`. Only a pending weighted pick qualifies, a
+/// scheduled or suggested word was chosen by someone
+fn reroll_candidate(s: &BotState, date: NaiveDate) -> Result<String, String> {
+    let Some(entry) = s.history.iter().rev().find(|e| e.date == date) else {
+        return Err(format!("No word has been picked for {date} yet."));
+    };
+    if entry.announced {
+        return Err(format!("The word for {date} has already been announced."));
+    }
+    match entry.source {
+        Some(PickSource::Weighted) => Ok(entry.word.clone()),
+        Some(PickSource::Queue(_)) => Err(format!(
+            "The word for {date} was suggested by a member, not auto-picked."
+        )),
+        Some(PickSource::Scheduled) => Err(format!(
+            "The word for {date} was scheduled by an admin, not auto-picked."
+        )),
+        None => Err(format!(
+            "The word for {date} was picked before the bot recorded how, it can't be rerolled."
+        )),
+    }
+}
+
+/// Show the bot's version and uptime
+#[poise::command(slash_command)]
+pub async fn about(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    let uptime = ctx.data().started_at.elapsed().as_secs();
+    let mut msg = format!(
+        "starting-word-shenanigans v{}\nUptime: {}d {}h {}m\nTimezone: {}",
+        env!("CARGO_PKG_VERSION"),
+        uptime / 86_400,
+        uptime % 86_400 / 3_600,
+        uptime % 3_600 / 60,
+        ctx.data().timezone
+    );
+    if let Ok(guild) = guild_of(ctx) {
+        let paused = guild.paused.load(Ordering::Relaxed);
+        msg.push_str(if paused {
+            "\nAnnouncements: paused"
+        } else {
+            "\nAnnouncements: running"
+        });
+    }
+    ctx.send(CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Chart the score distribution of announced words against the dictionary
+#[cfg(feature = "charts")]
+#[poise::command(slash_command, guild_only)]
+pub async fn statschart(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let dict = ctx.data().dict();
+    let all: Vec<f64> = dict.values().copied().collect();
+    let announced: Vec<f64> = guild.store.with(|s| {
+        s.history
+            .iter()
+            .filter(|e| e.announced)
+            .filter_map(|e| dict.get(&e.word).copied())
+            .collect()
+    });
+    if all.is_empty() {
+        ctx.say("The dictionary is empty.").await?;
+        return Ok(());
+    }
+
+    let png =
+        tokio::task::spawn_blocking(move || chart::score_histogram_png(&all, &announced)).await??;
+    let lo = dict.values().copied().fold(f64::INFINITY, f64::min);
+    let hi = dict.values().copied().fold(f64::NEG_INFINITY, f64::max);
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "Score distribution from {lo:.1} (left) to {hi:.1} (right).\n\
+                 Grey: all dictionary words, blue: announced words."
+            ))
+            .attachment(serenity::all::CreateAttachment::bytes(png, "scores.png")),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Explain why a date's word scored where it did, with the best alternatives at the time
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn explain(
+    ctx: Ctx<'_>,
+    #[description = "Date to explain (YYYY-MM-DD)"] date: String,
+) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let reply = |msg: String| ctx.send(CreateReply::default().content(msg).ephemeral(true));
+
+    let date = match parse_date_arg(&date) {
+        Ok(d) => d,
+        Err(msg) => {
+            reply(msg).await?;
+            return Ok(());
+        }
+    };
+    let state = ctx.data();
+    let found = guild.store.with(|s| {
+        let entry = s.history.iter().rev().find(|e| e.date == date).cloned()?;
+        // what was off limits when the word was picked, ignoring anything picked later
+        Some((entry, state.excluded(&s.as_of(date), date)))
+    });
+    let Some((entry, excluded)) = found else {
+        reply(format!("No word recorded for {date}.")).await?;
+        return Ok(());
+    };
+
+    let dict = state.dict();
+    let Some(&score) = dict.get(&entry.word) else {
+        reply(format!(
+            "`{}` ({date}) is no longer in the dictionary.",
+            entry.word
+        ))
+        .await?;
+        return Ok(());
+    };
+    let mut ranked: Vec<(&String, f64)> = dict.iter().map(|(w, &s)| (w, s)).collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    let rank = ranked
+        .iter()
+        .position(|(w, _)| **w == entry.word)
+        .unwrap_or(0)
+        + 1;
+
+    let how = match entry.suggested_by {
+        Some(user) => format!("suggested by <@{user}>"),
+        None => "auto-picked".to_owned(),
+    };
+    let mut msg = format!(
+        "{date} — `{}` ({how})\nScore: {score:.3}, rank {rank} of {}\nTop alternatives then:",
+        entry.word,
+        ranked.len()
+    );
+    let alternatives = ranked
+        .iter()
+        .filter(|(w, _)| **w != entry.word && !excluded.contains(*w))
+        .take(3);
+    for (w, s) in alternatives {
+        msg.push_str(&format!("\n`{w}` — {s:.3}"));
+    }
+    reply(msg).await?;
+    Ok(())
+}
+
+/// Show how each scoring feature adds up to a word's score
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn inspect(
+    ctx: Ctx<'_>,
+    #[description = "5-letter word"]
+    #[autocomplete = "autocomplete_word"]
+    word: String,
+) -> anyhow::Result<()> {
+    let state = ctx.data();
+    let opts = &state.dict_options;
+    let w = words::normalize(&word, opts.fold_accents);
+    if !opts.is_valid_word(&w) {
+        ctx.send(
+            CreateReply::default()
+                .content("Provide a 5-letter word using only the game's letters.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let breakdown = state.corpus.breakdown(&w, state.current_weights(), opts);
+    let mut table = format!(
+        "{:<18} {:>9} {:>7} {:>9}\n",
+        "feature", "value", "weight", "product"
+    );
+    for t in breakdown.terms.iter().filter(|t| t.value != 0.0) {
+        // rows breaking out part of the term above are indented, they're not added again
+        let name = match t.part_of {
+            Some(_) => format!("  {}", t.name),
+            None => t.name.to_owned(),
+        };
+        table.push_str(&format!(
+            "{:<18} {:>9.3} {:>7.2} {:>9.3}\n",
+            name,
+            t.value,
+            t.weight,
+            t.product()
+        ));
+    }
+    table.push_str(&format!("{:<18} {:>27.3}\n", "total", breakdown.total()));
+
+    let note = if state.dict().contains_key(&w) {
+        ""
+    } else {
+        "\nNot in the dictionary, scored as if it were."
+    };
+    let msg = format!("`{w}`, features that are zero left out:\n```\n{table}```{note}");
+    ctx.send(CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Compare two words' scores and the features that set them apart
+#[poise::command(slash_command, guild_only)]
+pub async fn compare(
+    ctx: Ctx<'_>,
+    #[description = "First word"]
+    #[autocomplete = "autocomplete_word"]
+    word_a: String,
+    #[description = "Second word"]
+    #[autocomplete = "autocomplete_word"]
+    word_b: String,
+) -> anyhow::Result<()> {
+    const MAX_FEATURES: usize = 6;
+
+    let state = ctx.data();
+    let opts = &state.dict_options;
+    let dict = state.dict();
+    let a = words::normalize(&word_a, opts.fold_accents);
+    let b = words::normalize(&word_b, opts.fold_accents);
+    if let Some(missing) = [&a, &b].into_iter().find(|w| !dict.contains_key(*w)) {
+        ctx.send(
+            CreateReply::default()
+                .content(format!("`{missing}` is not in the dictionary."))
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let wt = state.current_weights();
+    let score_a = state.corpus.breakdown(&a, wt, opts);
+    let score_b = state.corpus.breakdown(&b, wt, opts);
+    let mut diffs: HashMap<&str, f64> = HashMap::new();
+    for t in score_a.terms.iter().filter(|t| t.part_of.is_none()) {
+        *diffs.entry(t.name).or_default() += t.product();
+    }
+    for t in score_b.terms.iter().filter(|t| t.part_of.is_none()) {
+        *diffs.entry(t.name).or_default() -= t.product();
+    }
+    let mut diffs: Vec<(&str, f64)> = diffs.into_iter().filter(|(_, d)| *d != 0.0).collect();
+    diffs.sort_by(|x, y| y.1.abs().total_cmp(&x.1.abs()).then_with(|| x.0.cmp(y.0)));
+
+    let (total_a, total_b) = (score_a.total(), score_b.total());
+    let weirder = if total_a >= total_b { &a } else { &b };
+    let mut msg = format!(
+        "`{a}` scores {total_a:.3}, `{b}` scores {total_b:.3}, so `{weirder}` is the weirder \
+         word.\nBiggest differences:"
+    );
+    if diffs.is_empty() {
+        msg.push_str(" none");
+    }
+    for (name, d) in diffs.into_iter().take(MAX_FEATURES) {
+        let (ahead, by) = if d > 0.0 { (&a, d) } else { (&b, -d) };
+        msg.push_str(&format!("\n{name}: +{by:.3} for `{ahead}`"));
+    }
+    ctx.send(CreateReply::default().content(msg)).await?;
+    Ok(())
+}
+
+/// Check the dictionary file for problems without reloading it
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn dictlint(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    let state = ctx.data();
+    let path = state.dict_path.clone();
+    let opts = Arc::clone(&state.dict_options);
+    // the file can be large, read it off the async runtime
+    let loaded =
+        tokio::task::spawn_blocking(move || Corpus::load_with_report(&*path, &opts)).await?;
+    let msg = match loaded {
+        Ok((_, r)) => format!(
+            "`{}`: {} words usable\n\
+             Blank lines: {}\n\
+             Wrong length: {}\n\
+             Letters outside the alphabet: {}\n\
+             Proper nouns left out: {}\n\
+             Duplicates: {}",
+            state.dict_path,
+            r.kept,
+            r.blank,
+            r.wrong_length,
+            r.bad_letters,
+            r.proper_nouns,
+            r.duplicates
+        ),
+        Err(e) => format!("Couldn't read `{}`: {e:#}", state.dict_path),
+    };
+    ctx.send(CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Download a copy of this server's saved state
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn backup(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    // only word history, queue and user ids, nothing admins can't already see
+    let bytes = guild.store.snapshot_bytes()?;
+    let name = format!("state-{}.json", ctx.data().target_date());
+    ctx.send(
+        CreateReply::default()
+            .content("Current state attached.")
+            .attachment(serenity::all::CreateAttachment::bytes(bytes, name))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Replace this server's saved state with an uploaded backup
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn restore(
+    ctx: Ctx<'_>,
+    #[description = "State file from /backup"] file: serenity::all::Attachment,
+) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let reply = |msg: String| ctx.send(CreateReply::default().content(msg).ephemeral(true));
+
+    let bytes = file.download().await?;
+    // parsed in full before anything is touched, older files load the same way as on startup
+    let restored: BotState = match serde_json::from_slice(&bytes) {
+        Ok(s) => s,
+        Err(e) => {
+            reply(format!(
+                "Rejected: `{}` isn't a valid state file ({e}).",
+                file.filename
+            ))
+            .await?;
+            return Ok(());
+        }
+    };
+    let summary = format!(
+        "Restored {} history entries, {} queued and {} scheduled words.",
+        restored.history.len(),
+        restored.queue.len(),
+        restored.scheduled.len()
+    );
+    {
+        let _guard = guild.run_lock.lock().await;
+        guild.paused.store(restored.paused, Ordering::Relaxed);
+        guild.store.replace(restored)?;
+    }
+    info!(
+        "{} restored state from {}",
+        ctx.author().name,
+        file.filename
+    );
+    reply(summary).await?;
+    Ok(())
+}
+
+/// What `run_once` would use for `target`, from `next_pick`
+#[derive(Debug, PartialEq)]
+enum NextPick {
+    Recorded,
+    Scheduled,
+    Queued(UserId, String),
+    Weighted,
+}
+
+/// Checks the same sources in the same order as `run_once`, without taking anything
+fn next_pick(state: &AppState, s: &BotState, target: NaiveDate) -> NextPick {
+    if s.history.iter().any(|e| e.date == target) {
+        return NextPick::Recorded;
+    }
+    let dict = state.dict();
+    if s.scheduled
+        .get(&target)
+        .is_some_and(|w| dict.contains_key(w))
+    {
+        return NextPick::Scheduled;
+    }
+    // same checks as take_from_queue, without dropping anything
+    let excluded = state.excluded(s, target);
+    queue_order(state, &s.queue)
+        .into_iter()
+        .map(|i| &s.queue[i])
+        .find(|q| {
+            dict.contains_key(&q.word)
+                && !excluded.contains(&q.word)
+                && !s.suggester_on_cooldown(q.user, target, state.suggester_cooldown_days)
+        })
+        .map_or(NextPick::Weighted, |q| {
+            NextPick::Queued(q.user, q.word.clone())
+        })
+}
+
+/// Show which queued suggestion is next in line
+#[poise::command(slash_command, guild_only)]
+pub async fn peek(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let state = ctx.data();
+    let target = state.target_date();
+    let next = guild.store.with(|s| next_pick(state, s, target));
+
+    let msg = match next {
+        NextPick::Recorded => format!(
+            "The word for {target} has already been picked, the queue waits for the day after."
+        ),
+        NextPick::Scheduled => {
+            format!("{target} has a scheduled word, the queue waits for the day after.")
+        }
+        NextPick::Queued(user, w) => {
+            let w = if state.spoiler {
+                format!("||`{w}`||")
+            } else {
+                format!("`{w}`")
+            };
+            format!("Next queued word: {w} (suggested by <@{user}>)")
+        }
+        NextPick::Weighted => "The queue is empty, the next word will be auto-selected.".to_owned(),
+    };
+    ctx.send(
+        CreateReply::default()
+            .content(msg)
+            .allowed_mentions(serenity::all::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Remove a member's queued suggestions and anonymise their past ones
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn forgetuser(
+    ctx: Ctx<'_>,
+    #[description = "Member to forget"] user: serenity::all::User,
+) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let (dequeued, anonymised) = guild.store.with_mut(|s| {
+        let before = s.queue.len();
+        s.queue.retain(|q| q.user != user.id);
+        s.reuse_requests.retain(|r| r.user != user.id);
+        let mut anonymised = 0;
+        for e in s
+            .history
+            .iter_mut()
+            .filter(|e| e.suggested_by == Some(user.id))
+        {
+            e.suggested_by = None;
+            e.note = None;
+            anonymised += 1;
+        }
+        (before - s.queue.len(), anonymised)
+    });
+    info!("{} forgot user {}", ctx.author().name, user.id);
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "Forgot <@{}>: removed {dequeued} queued words and anonymised {anonymised} past words.",
+                user.id
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Pick and record the words for the next few days ahead of time
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn pregenerate(
+    ctx: Ctx<'_>,
+    #[description = "Number of puzzle days to fill, starting with the next announcement"]
+    #[min = 1]
+    #[max = 31]
+    days: u8,
+) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let state = ctx.data();
+    let first = state.target_date();
+
+    let mut lines = Vec::new();
+    {
+        let _guard = guild.run_lock.lock().await;
+        // skipped weekdays have no puzzle, a word picked for one would be used up unannounced
+        for date in state.puzzle_dates(first, usize::from(days)) {
+            let existing = guild
+                .store
+                .with(|s| s.history.iter().find(|e| e.date == date).cloned());
+            if let Some(e) = existing {
+                lines.push(format!("{date} — `{}` (already picked)\n", e.word));
+                continue;
+            }
+            // seeded by date so the same state always produces the same plan
+            match select_word(state, guild, date, &mut date_rng(date)) {
+                Some((w, PickSource::Queue(user))) => {
+                    lines.push(format!("{date} — `{w}` (from <@{user}>)\n"));
+                }
+                Some((w, PickSource::Scheduled)) => {
+                    lines.push(format!("{date} — `{w}` (scheduled)\n"));
+                }
+                Some((w, PickSource::Weighted)) => lines.push(format!("{date} — `{w}`\n")),
+                None => {
+                    lines.push(format!("{date} — no word left to pick\n"));
+                    break;
+                }
+            }
+        }
+    }
+    info!("{} pregenerated {days} day(s)", ctx.author().name);
+
+    for msg in split_messages("Planned words:\n".to_owned(), lines) {
+        ctx.send(CreateReply::default().content(msg).ephemeral(true))
+            .await?;
+    }
+    Ok(())
+}
+
+/// List the words scheduled for upcoming dates
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn scheduled(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let target = ctx.data().target_date();
+    let mut upcoming: Vec<(NaiveDate, String)> = guild.store.with(|s| {
+        s.scheduled
+            .iter()
+            .filter(|(d, _)| **d >= target)
+            .map(|(d, w)| (*d, w.clone()))
+            .collect()
+    });
+    if upcoming.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .content("No words are scheduled.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    upcoming.sort_unstable();
+
+    let lines = upcoming.into_iter().map(|(d, w)| format!("{d} — `{w}`\n"));
+    for msg in split_messages("Scheduled words:\n".to_owned(), lines) {
+        ctx.send(CreateReply::default().content(msg).ephemeral(true))
+            .await?;
+    }
+    Ok(())
+}
+
+/// List queued words the next run would drop, so they can be cleaned up ahead of time
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn queuecheck(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let state = ctx.data();
+    let target = state.target_date();
+    let dict = state.dict();
+    let problems: Vec<String> = guild.store.with(|s| {
+        let excluded = state.excluded(s, target);
+        s.queue
+            .iter()
+            .enumerate()
+            .filter_map(|(i, QueuedWord { user, word, .. })| {
+                let problem = queued_word_problem(&dict, &excluded, &word.to_lowercase())?;
+                Some(format!("#{} `{word}` from <@{user}> — {problem}\n", i + 1))
+            })
+            .collect()
+    });
+    if problems.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .content("Every queued word is still valid.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let header = format!("{} queued words would be dropped:\n", problems.len());
+    for msg in split_messages(header, problems) {
+        ctx.send(CreateReply::default().content(msg).ephemeral(true))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Remove queued words the next run would drop, and list them in the mod channel
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn dropinvalid(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let state = ctx.data();
+    let target = state.target_date();
+    let dict = state.dict();
+    let dropped: Vec<(UserId, String, &str)> = guild.store.with_mut(|s| {
+        let excluded = state.excluded(s, target);
+        let mut dropped = Vec::new();
+        s.queue.retain(
+            |q| match queued_word_problem(&dict, &excluded, &q.word.to_lowercase()) {
+                Some(problem) => {
+                    dropped.push((q.user, q.word.clone(), problem));
+                    false
+                }
+                None => true,
+            },
+        );
+        dropped
+    });
+    if dropped.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .content("Every queued word is still valid.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    info!(
+        "{} dropped {} invalid queued words",
+        ctx.author().name,
+        dropped.len()
+    );
+
+    let users: HashSet<UserId> = dropped.iter().map(|(u, _, _)| *u).collect();
+    let lines: Vec<String> = dropped
+        .iter()
+        .map(|(user, word, problem)| format!("`{word}` from <@{user}> — {problem}\n"))
+        .collect();
+    let header = format!(
+        "Dropped {} queued words from {} members:\n",
+        dropped.len(),
+        users.len()
+    );
+    // without a mod channel, or if posting there fails, the list goes back to whoever ran this
+    let mut posted_in = guild.mod_channel_id;
+    if let Some(channel_id) = posted_in {
+        for msg in split_messages(header.clone(), lines.clone()) {
+            let create = serenity::all::CreateMessage::new()
+                .content(msg)
+                .allowed_mentions(serenity::all::CreateAllowedMentions::new());
+            if let Err(e) = channel_id.send_message(ctx.http(), create).await {
+                error!("Failed to post dropped queue words: {:?}", e);
+                posted_in = None;
+                break;
+            }
+        }
+    }
+    match posted_in {
+        Some(channel_id) => {
+            let msg = format!(
+                "Dropped {} queued words from {} members, listed in <#{channel_id}>.",
+                dropped.len(),
+                users.len()
+            );
+            ctx.send(CreateReply::default().content(msg).ephemeral(true))
+                .await?;
+        }
+        None => {
+            for msg in split_messages(header, lines) {
+                ctx.send(CreateReply::default().content(msg).ephemeral(true))
+                    .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Show what the auto-picker would choose for a past date, given the history before it
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn replay(
+    ctx: Ctx<'_>,
+    #[description = "Date to replay (YYYY-MM-DD)"] date: String,
+) -> anyhow::Result<()> {
+    // uses today's dictionary and weights, and the same per-date seed as `/pregenerate`
+
+    let guild = guild_of(ctx)?;
+    let state = ctx.data();
+    let date = match parse_date_arg(&date) {
+        Ok(d) => d,
+        Err(msg) => {
+            ctx.send(CreateReply::default().content(msg).ephemeral(true))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let (picked, used_before, actual) = guild.store.with(|s| {
+        let actual = s
+            .history
+            .iter()
+            .find(|e| e.date == date)
+            .map(|e| e.word.clone());
+        let used_before = s.history.iter().filter(|e| e.date < date).count();
+        (replay_pick(state, s, date), used_before, actual)
+    });
+
+    let mut msg = match picked {
+        Some(w) => format!("The auto-picker would choose `{w}` for {date}"),
+        None => format!("The auto-picker would find nothing to pick for {date}"),
+    };
+    msg.push_str(&format!(" ({used_before} words used before then)."));
+    if let Some(actual) = actual {
+        msg.push_str(&format!("\nThe word actually recorded was `{actual}`."));
+    }
+    ctx.send(CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// The rng `/pregenerate` and `/replay` pick `date`'s word with
+fn date_rng(date: NaiveDate) -> rand::rngs::StdRng {
+    use rand::SeedableRng;
+
+    rand::rngs::StdRng::seed_from_u64(date.num_days_from_ce() as u64)
+}
+
+/// The weighted pick for `date` made against `s` rolled back to that morning, with the same seed
+/// `/pregenerate` uses
+fn replay_pick(state: &AppState, s: &BotState, date: NaiveDate) -> Option<String> {
+    let past = s.as_of(date);
+    let excluded = state.excluded(&past, date);
+    pick_from_state(state, &past, date, &excluded, &mut date_rng(date))
+}
+
+/// What the weighted picker draws from for one date, owned so it can move to a blocking task
+struct EligiblePool {
+    dict: Arc<HashMap<String, f64>>,
+    excluded: HashSet<String>,
+    recent_rare: HashSet<char>,
+    penalty: f64,
+    easy_mode: bool,
+}
+
+impl EligiblePool {
+    /// The candidates and their odds, as `pick_from_state` weighs them
+    fn weighted(&self) -> Option<(Vec<&str>, rand::distr::weighted::WeightedIndex<f64>)> {
+        words::weighted_pool(
+            &self.dict,
+            Some(&self.excluded),
+            Some(SAMPLE_ALPHA),
+            self.easy_mode,
+            |w| words::recent_letter_multiplier(w, &self.recent_rare, self.penalty),
+        )
+    }
+}
+
+/// The pool `/samplepicks` and `/distribution` draw from for `target`
+fn eligible_pool(state: &AppState, s: &BotState, target: NaiveDate) -> EligiblePool {
+    let recent = s.recent_words(state.recent_letter_window);
+    EligiblePool {
+        dict: state.dict(),
+        excluded: state.excluded(s, target),
+        recent_rare: words::rare_letters(recent.iter().map(String::as_str)),
+        penalty: state.recent_letter_penalty,
+        easy_mode: state.easy_mode,
+    }
+}
+
+/// Sample the auto-picker many times to see how concentrated its choices are
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn distribution(
+    ctx: Ctx<'_>,
+    #[description = "Number of picks to simulate"]
+    #[min = 100]
+    #[max = 100000]
+    samples: u32,
+) -> anyhow::Result<()> {
+    use rand::{SeedableRng, distr::Distribution};
+
+    let guild = guild_of(ctx)?;
+    let state = ctx.data();
+    let target = state.target_date();
+    let pool = guild.store.with(|s| eligible_pool(state, s, target));
+
+    let outcome = tokio::task::spawn_blocking(move || {
+        let (keys, dist) = pool.weighted()?;
+        // fixed seed so repeated runs on the same state are comparable
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let mut counts = vec![0u32; keys.len()];
+        for _ in 0..samples {
+            counts[dist.sample(&mut rng)] += 1;
+        }
+        let distinct = counts.iter().filter(|&&c| c > 0).count();
+        let mut top: Vec<(u32, String)> = counts
+            .into_iter()
+            .zip(keys)
+            .filter(|(c, _)| *c > 0)
+            .map(|(c, w)| (c, w.to_owned()))
+            .collect();
+        top.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        top.truncate(10);
+        Some((distinct, top))
+    })
+    .await?;
+
+    let msg = match outcome {
+        None => "No words are left to pick from.".to_owned(),
+        Some((distinct, top)) => {
+            let mut msg = format!(
+                "{samples} simulated picks (alpha {SAMPLE_ALPHA}) hit {distinct} distinct words.\nMost picked:"
+            );
+            for (count, w) in top {
+                let pct = f64::from(count) * 100.0 / f64::from(samples);
+                msg.push_str(&format!("\n`{w}` — {count} ({pct:.2}%)"));
+            }
+            msg
+        }
+    };
+    ctx.send(CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Draw some words from the auto-picker to see what the current settings produce
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn samplepicks(
+    ctx: Ctx<'_>,
+    #[description = "Number of words to draw"]
+    #[min = 1]
+    #[max = 50]
+    n: u8,
+    #[description = "Seed, to draw the same words again (default: random)"] seed: Option<u64>,
+) -> anyhow::Result<()> {
+    use rand::{Rng, SeedableRng, distr::Distribution};
+
+    let guild = guild_of(ctx)?;
+    let state = ctx.data();
+    let target = state.target_date();
+    let pool = guild.store.with(|s| eligible_pool(state, s, target));
+    let seed = seed.unwrap_or_else(|| rand::rng().random());
+
+    let picks = tokio::task::spawn_blocking(move || {
+        let (keys, dist) = pool.weighted()?;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+        // each draw is independent, the same word can come up more than once
+        let picks: Vec<(String, f64)> = (0..n)
+            .map(|_| {
+                let w = keys[dist.sample(&mut rng)];
+                (w.to_owned(), pool.dict[w])
+            })
+            .collect();
+        Some(picks)
+    })
+    .await?;
+
+    let Some(picks) = picks else {
+        ctx.send(
+            CreateReply::default()
+                .content("No words are left to pick from.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    };
+    let lines = picks
+        .into_iter()
+        .map(|(w, score)| format!("`{w}` — {score:.3}\n"));
+    let header = format!("{n} sample picks (seed {seed}):\n");
+    for msg in split_messages(header, lines) {
+        ctx.send(CreateReply::default().content(msg).ephemeral(true))
+            .await?;
+    }
+    Ok(())
+}
+
+/// Recompute every word's score with the current weights
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn rescore(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    ctx.data().rescore();
+    info!("{} rescored the dictionary", ctx.author().name);
+    ctx.send(
+        CreateReply::default()
+            .content(format!(
+                "Rescored {} words with the current weights.",
+                ctx.data().dict().len()
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Move announcements to another channel
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn setchannel(
+    ctx: Ctx<'_>,
+    #[description = "Channel to announce in"]
+    #[channel_types("Text", "News")]
+    channel: serenity::all::GuildChannel,
+) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let msg = if channel.guild_id != guild.id {
+        "That channel isn't in this server.".to_owned()
+    } else {
+        guild.store.with_mut(|s| {
+            s.announce_channel_id = Some(channel.id);
+            // any announcement thread hangs off the old channel
+            s.thread_id = None;
+        });
+        info!(
+            "{} moved announcements in {} to {}",
+            ctx.author().name,
+            guild.id,
+            channel.id
+        );
+        format!("Announcements will now be posted in <#{}>.", channel.id)
+    };
+    ctx.send(CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Stop announcements in this server until /resume
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn pause(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    set_paused(ctx, true).await
+}
+
+/// Restart announcements after /pause
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn resume(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    set_paused(ctx, false).await
+}
+
+async fn set_paused(ctx: Ctx<'_>, paused: bool) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let was = guild.paused.swap(paused, Ordering::Relaxed);
+    guild.store.with_mut(|s| s.paused = paused);
+    let msg = match (was, paused) {
+        (true, true) => "Announcements were already paused.",
+        (false, true) => "Announcements paused, use /resume to restart them.",
+        (false, false) => "Announcements weren't paused.",
+        (true, false) => "Announcements resumed.",
+    };
+    if was != paused {
+        info!("{} set paused to {paused}", ctx.author().name);
+    }
+    ctx.send(CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Show which starting letters haven't been used for the longest
+#[poise::command(slash_command, guild_only)]
+pub async fn letterdrought(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let target = ctx.data().target_date();
+    let mut letters: Vec<char> = ctx
+        .data()
+        .dict()
+        .keys()
+        .filter_map(|w| w.chars().next())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    letters.sort_unstable();
+
+    // only words already revealed, so an upcoming word's letter isn't given away
+    let last_used: HashMap<char, NaiveDate> = guild.store.with(|s| {
+        let mut last = HashMap::new();
+        for (w, date) in s.as_of(target).last_used() {
+            if let Some(c) = w.chars().next() {
+                let d = last.entry(c).or_insert(date);
+                *d = (*d).max(date);
+            }
+        }
+        last
+    });
+
+    let mut droughts: Vec<(char, Option<NaiveDate>)> = letters
+        .into_iter()
+        .map(|c| (c, last_used.get(&c).copied()))
+        .collect();
+    droughts.sort_by_key(|(c, d)| (*d, *c));
+
+    let lines = droughts.into_iter().take(10).map(|(c, d)| match d {
+        Some(d) => format!(
+            "`{}` — {} days (last on {d})\n",
+            c.to_uppercase(),
+            (target - d).num_days()
+        ),
+        None => format!("`{}` — never\n", c.to_uppercase()),
+    });
+    for msg in split_messages("Longest letter droughts:\n".to_owned(), lines) {
+        ctx.send(CreateReply::default().content(msg)).await?;
+    }
+    Ok(())
+}
+
+/// Check the bot can read its state and dictionary and when it last announced
+#[poise::command(slash_command, guild_only)]
+pub async fn health(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let mut problems = Vec::new();
+
+    if let Some(problem) = state_file_problem(Path::new(guild.store.path())) {
+        problems.push(problem);
+    }
+    let words = ctx.data().dict().len();
+    if words == 0 {
+        problems.push("dictionary is empty".to_owned());
+    }
+    if guild.paused.load(Ordering::Relaxed) {
+        problems.push("announcements are paused".to_owned());
+    }
+    let last = guild.store.with(|s| {
+        s.history
+            .iter()
+            .filter(|e| e.announced)
+            .map(|e| e.date)
+            .max()
+    });
+
+    let mut msg = if problems.is_empty() {
+        "Status: ok".to_owned()
+    } else {
+        format!("Status: degraded ({})", problems.join(", "))
+    };
+    msg.push_str(&format!("\nDictionary: {words} words"));
+    msg.push_str(&match last {
+        Some(d) => format!("\nLast announced: {d}"),
+        None => "\nLast announced: never".to_owned(),
+    });
+    ctx.send(CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// What's wrong with the state file at `path`, if it can't be read back as a `BotState`
+fn state_file_problem(path: &Path) -> Option<String> {
+    // a missing file is fine before the first save, anything else means saves will fail too
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => return Some(format!("state file unreadable: {e}")),
+    };
+    serde_json::from_slice::<BotState>(&bytes)
+        .err()
+        .map(|e| format!("state file corrupt: {e}"))
+}
+
+/// Show how often suggestions were rejected, by reason
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn rejections(ctx: Ctx<'_>) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let c = guild.store.with(|s| s.rejections);
+    let msg = format!(
+        "Rejected suggestions:\n\
+         Hidden or look-alike characters: {}\n\
+         Wrong length or letters: {}\n\
+         Not in dictionary: {}\n\
+         Already used: {}\n\
+         Already queued: {}\n\
+         Over the queue cap: {}",
+        c.lookalike,
+        c.bad_letters,
+        c.not_in_dictionary,
+        c.already_used,
+        c.already_queued,
+        c.queue_cap
+    );
+    ctx.send(CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Run tonight's pick and announcement now
+#[poise::command(slash_command, guild_only, check = "is_admin")]
+pub async fn runnow(
+    ctx: Ctx<'_>,
+    #[description = "Reveal the word a letter at a time (default: SLOW_REVEAL)"]
+    slow_reveal: Option<bool>,
+) -> anyhow::Result<()> {
+    let guild = guild_of(ctx)?;
+    let state = ctx.data();
+    ctx.defer_ephemeral().await?;
+    info!("{} triggered a run", ctx.author().name);
+
+    let slow_reveal = slow_reveal.unwrap_or(state.slow_reveal);
+    let sink = ConfiguredSink::new(ctx.serenity_context(), state, guild);
+    let members = ctx.serenity_context();
+    let msg = match run_once(&sink, members, state, guild, slow_reveal).await {
+        Ok(RunOutcome::Paused) => "Announcements are paused, nothing was done.".to_owned(),
+        Ok(RunOutcome::SkippedDay(date)) => {
+            format!("{date} is a skipped day, nothing was announced.")
+        }
+        Ok(RunOutcome::Reused(date, w)) => {
+            format!("{date} already had `{w}`, announced it again.")
+        }
+        Ok(RunOutcome::AlreadyAnnounced(date, w)) => {
+            format!("`{w}` was already announced for {date}, nothing was done.")
+        }
+        Ok(RunOutcome::Picked(date, w, source)) => {
+            let from = match source {
+                PickSource::Scheduled => "the schedule".to_owned(),
+                PickSource::Queue(user) => format!("the queue (suggested by <@{user}>)"),
+                PickSource::Weighted => "the weighted picker".to_owned(),
+            };
+            format!("Announced `{w}` for {date}, from {from}.")
+        }
+        Err(e) => format!("Run failed: {e}"),
+    };
+    ctx.send(CreateReply::default().content(msg).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::all::MessageId;
+
+    use super::*;
+
+    /// A state with `words` as the whole dictionary and every optional behaviour turned off
+    fn test_state(words: &[&str]) -> AppState {
+        let dict_options = DictOptions::default();
+        let corpus = Corpus::from_words(
+            words.iter().map(|w| (*w).to_owned()).collect(),
+            &dict_options,
+        );
+        let wt = Weights::default();
+        let dictionary = Arc::new(corpus.rescore(wt, &dict_options));
+        AppState {
+            guilds: Arc::default(),
+            timezone: Tz::UTC,
+            admin_role_id: None,
+            sorted_scores: Arc::new(RwLock::new(Arc::new(sorted_scores(&dictionary)))),
+            dictionary: Arc::new(RwLock::new(dictionary)),
+            weights: Arc::new(RwLock::new(wt)),
+            weights_path: None,
+            corpus: Arc::new(corpus),
+            dict_path: "".into(),
+            reuse_after_days: None,
+            history_keep_days: None,
+            date_format: DEFAULT_DATE_FORMAT.into(),
+            low_pool_threshold: 0,
+            reuse_oldest_when_exhausted: false,
+            spoiler: false,
+            suggester_cooldown_days: 0,
+            announce_jitter_minutes: 0,
+            announce_spacing: std::time::Duration::ZERO,
+            dict_options: Arc::new(dict_options),
+            announce_lead_days: 1,
+            started_at: std::time::Instant::now(),
+            recent_letter_window: 0,
+            recent_letter_penalty: 1.0,
+            announce_thread_name: None,
+            easy_mode: false,
+            credit_display_names: false,
+            quiet_credit: false,
+            show_streak: false,
+            slow_reveal: false,
+            reveal: false,
+            dry_run: false,
+            queue_cap_per_user: 0,
+            queue_aging: false,
+            reuse_requests: false,
+            rejection_memory: std::time::Duration::ZERO,
+            skip_weekdays: Arc::default(),
+            surprise_weekdays: Arc::default(),
+            digest_weekday: None,
+            no_word_message: None,
+            announce_reactions: Arc::default(),
+            decisions_path: None,
+            webhook_name: None,
+            webhook_avatar_url: None,
+        }
+    }
+
+    /// A server whose state file is a fresh one in the temp dir, unique to `name`
+    fn test_guild(name: &str) -> GuildState {
+        let path = std::env::temp_dir().join(format!(
+            "starting-word-test-{}-{name}.json",
+            std::process::id()
+        ));
+        fs::remove_file(&path).ok();
+        let cfg = GuildCfg {
+            guild_id: Some(1),
+            announce_channel_id: 1,
+            role_id: 0,
+            state_path: path.to_string_lossy().into_owned(),
+            mod_channel_id: None,
+            audit_channel_id: None,
+            public_view_path: None,
+            webhook_url: None,
+            test_channel_id: None,
+        };
+        GuildState::load(GuildId::new(1), &cfg).unwrap()
+    }
+
+    /// Keeps everything it's asked to post, each announcement getting the next message id
+    #[derive(Default)]
+    struct RecordingSink {
+        announced: parking_lot::Mutex<Vec<(NaiveDate, String)>>,
+        edited: parking_lot::Mutex<Vec<(NaiveDate, String)>>,
+    }
+
+    impl AnnounceSink for RecordingSink {
+        async fn announce(
+            &self,
+            date: NaiveDate,
+            word: &str,
+            _suggested_by: Option<&str>,
+        ) -> Result<Option<PostedMessage>, SelectError> {
+            // give a concurrent run the chance to get in between picking and posting
+            tokio::task::yield_now().await;
+            let mut announced = self.announced.lock();
+            announced.push((date, word.to_owned()));
+            Ok(Some(PostedMessage {
+                channel_id: ChannelId::new(1),
+                message_id: MessageId::new(announced.len() as u64),
+            }))
+        }
+
+        async fn edit(
+            &self,
+            _message: PostedMessage,
+            date: NaiveDate,
+            word: &str,
+            _suggested_by: Option<&str>,
+        ) -> Result<(), SelectError> {
+            self.edited.lock().push((date, word.to_owned()));
+            Ok(())
+        }
+
+        async fn reveal(&self, _date: NaiveDate, _word: &str) -> Result<(), SelectError> {
+            Ok(())
+        }
+
+        async fn no_word(&self, _date: NaiveDate, _message: &str) -> Result<(), SelectError> {
+            Ok(())
+        }
+
+        async fn digest(&self, _text: &str) -> Result<(), SelectError> {
+            Ok(())
+        }
+
+        async fn mod_notice(&self, _text: &str) -> Result<(), SelectError> {
+            Ok(())
+        }
+    }
+
+    struct NoNames;
+
+    impl MemberNames for NoNames {
+        async fn display_name(&self, _guild: &GuildState, _user: UserId) -> Option<String> {
+            None
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_runs_announce_once() {
+        let state = test_state(&["crane", "slate", "pique", "fjord"]);
+        let guild = test_guild("concurrent_runs");
+        let sink = RecordingSink::default();
+
+        let (a, b) = tokio::join!(
+            run_once(&sink, &NoNames, &state, &guild, false),
+            run_once(&sink, &NoNames, &state, &guild, false),
+        );
+        assert!(a.is_ok() && b.is_ok());
+
+        let target = state.target_date();
+        let history: Vec<NaiveDate> = guild
+            .store
+            .with(|s| s.history.iter().map(|e| e.date).collect());
+        assert_eq!(history, vec![target]);
+        assert_eq!(sink.announced.lock().len(), 1);
+        // the run that lost the race finds the word already posted and only edits it
+        assert_eq!(*sink.edited.lock(), *sink.announced.lock());
+    }
+
+    #[test]
+    fn exhausted_pool_reuses_oldest_word() {
+        let mut state = test_state(&["crane", "slate"]);
+        let guild = test_guild("exhausted_pool");
+        let target = state.target_date();
+        guild.store.with_mut(|s| {
+            s.mark_used(
+                target - chrono::Duration::days(5),
+                "slate".to_owned(),
+                PickSource::Weighted,
+            );
+            s.mark_used(
+                target - chrono::Duration::days(9),
+                "crane".to_owned(),
+                PickSource::Weighted,
+            );
+        });
+
+        assert!(select_word(&state, &guild, target, &mut rand::rng()).is_none());
+
+        state.reuse_oldest_when_exhausted = true;
+        let (word, _) = select_word(&state, &guild, target, &mut rand::rng()).unwrap();
+        assert_eq!(word, "crane");
+        assert!(
+            guild
+                .store
+                .with(|s| s.history.iter().any(|e| e.date == target))
+        );
+    }
+
+    #[test]
+    fn long_listings_split_under_the_limit() {
+        let lines: Vec<String> = (0..200)
+            .map(|i| format!("2024-01-01 — `w{i:04}` with some padding\n"))
+            .collect();
+        let messages = split_messages("Header\n".to_owned(), lines.clone());
+
+        assert!(messages.len() > 1);
+        assert!(
+            messages
+                .iter()
+                .all(|m| m.chars().count() <= MESSAGE_CHAR_LIMIT)
+        );
+        // nothing dropped or reordered
+        assert_eq!(messages.concat(), format!("Header\n{}", lines.concat()));
+    }
+
+    #[test]
+    fn scheduled_word_overrides_the_weighted_pick() {
+        let state = test_state(&["crane", "slate", "fjord"]);
+        let guild = test_guild("scheduled_override");
+        let target = state.target_date();
+        guild
+            .store
+            .with_mut(|s| s.scheduled.insert(target, "slate".to_owned()));
+
+        let (word, source) = select_word(&state, &guild, target, &mut rand::rng()).unwrap();
+        assert_eq!(word, "slate");
+        assert!(matches!(source, PickSource::Scheduled));
+        assert!(guild.store.with(|s| s.scheduled.is_empty()));
+    }
+
+    #[test]
+    fn schedule_rejects_taken_dates_and_used_words() {
+        let state = test_state(&["crane", "slate", "fjord"]);
+        let target = state.target_date();
+        let mut s = BotState::default();
+        s.mark_used(target, "crane".to_owned(), PickSource::Weighted);
+
+        assert!(schedule_problem(&state, &s, target, "slate").is_err());
+        assert!(schedule_problem(&state, &s, target - chrono::Duration::days(1), "slate").is_err());
+        let next = target + chrono::Duration::days(1);
+        assert!(schedule_problem(&state, &s, next, "crane").is_err());
+        assert!(schedule_problem(&state, &s, next, "plonk").is_err());
+        assert!(schedule_problem(&state, &s, next, "slate").is_ok());
+    }
+
+    #[test]
+    fn reroll_only_replaces_pending_weighted_picks() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let mut s = BotState::default();
+        s.mark_used(day(1), "crane".to_owned(), PickSource::Weighted);
+        s.mark_used(day(2), "slate".to_owned(), PickSource::Scheduled);
+        s.mark_used(
+            day(3),
+            "adieu".to_owned(),
+            PickSource::Queue(UserId::new(7)),
+        );
+        s.mark_used(day(4), "fjord".to_owned(), PickSource::Weighted);
+        s.mark_announced(day(4), None);
+        s.mark_used(day(5), "pious".to_owned(), PickSource::Weighted);
+        s.history.last_mut().unwrap().source = None;
+
+        assert_eq!(reroll_candidate(&s, day(1)), Ok("crane".to_owned()));
+        assert!(
+            reroll_candidate(&s, day(2))
+                .unwrap_err()
+                .contains("scheduled")
+        );
+        assert!(
+            reroll_candidate(&s, day(3))
+                .unwrap_err()
+                .contains("suggested")
+        );
+        assert!(
+            reroll_candidate(&s, day(4))
+                .unwrap_err()
+                .contains("announced")
+        );
+        assert!(reroll_candidate(&s, day(5)).is_err());
+        assert!(reroll_candidate(&s, day(6)).is_err());
+    }
+
+    #[test]
+    fn picks_record_their_source() {
+        let state = test_state(&["crane", "slate"]);
+        let guild = test_guild("pick-source");
+        let target = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        guild
+            .store
+            .with_mut(|s| s.scheduled.insert(target, "slate".to_owned()));
+        select_word(&state, &guild, target, &mut rand::rng()).unwrap();
+        select_word(&state, &guild, target.succ_opt().unwrap(), &mut rand::rng()).unwrap();
+        let sources: Vec<_> = guild
+            .store
+            .with(|s| s.history.iter().map(|e| e.source).collect());
+        assert_eq!(
+            sources,
+            [Some(PickSource::Scheduled), Some(PickSource::Weighted)]
+        );
+    }
+
+    #[test]
+    fn select_word_returns_the_already_recorded_word() {
+        let state = test_state(&["crane", "slate", "adieu"]);
+        let guild = test_guild("select-recorded");
+        let target = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        guild.store.with_mut(|s| {
+            s.mark_used(
+                target,
+                "slate".to_owned(),
+                PickSource::Queue(UserId::new(7)),
+            )
+        });
+
+        let (word, source) = select_word(&state, &guild, target, &mut rand::rng()).unwrap();
+        assert_eq!(word, "slate");
+        assert_eq!(source, PickSource::Queue(UserId::new(7)));
+        assert_eq!(guild.store.with(|s| s.history.len()), 1);
+    }
+
+    #[test]
+    fn rescore_refreshes_the_sorted_scores() {
+        let state = test_state(&["crane", "fjord", "qwert"]);
+        let dict = state.dict();
+        assert_eq!(
+            state.words_below(dict["crane"]),
+            dict.values().filter(|&&s| s < dict["crane"]).count()
+        );
+
+        *state.weights.write() = Weights {
+            keyboard_run: 100.0,
+            ..Weights::default()
+        };
+        state.rescore();
+        assert_eq!(state.words_below(state.dict()["qwert"]), 2);
+        assert_eq!(state.sorted_scores.read().len(), 3);
+    }
+
+    #[test]
+    fn peek_checks_history_then_schedule_then_queue() {
+        let state = test_state(&["crane", "slate", "adieu"]);
+        let target = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let user = UserId::new(7);
+        let mut s = BotState::default();
+        assert_eq!(next_pick(&state, &s, target), NextPick::Weighted);
+
+        s.queue.push_back(QueuedWord {
+            user,
+            word: "adieu".to_owned(),
+            note: None,
+            skipped: 0,
+        });
+        assert_eq!(
+            next_pick(&state, &s, target),
+            NextPick::Queued(user, "adieu".to_owned())
+        );
+
+        s.scheduled.insert(target, "slate".to_owned());
+        assert_eq!(next_pick(&state, &s, target), NextPick::Scheduled);
+
+        s.mark_used(target, "crane".to_owned(), PickSource::Weighted);
+        assert_eq!(next_pick(&state, &s, target), NextPick::Recorded);
+    }
+
+    #[test]
+    fn duplicate_suggestion_names_who_queued_it() {
+        let state = test_state(&["crane", "slate"]);
+        let target = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let (alice, bob) = (UserId::new(7), UserId::new(8));
+        let mut s = BotState::default();
+        assert!(queue_word(&state, &mut s, target, alice, "slate", None).is_ok());
+        assert!(queue_word(&state, &mut s, target, alice, "crane", None).is_ok());
+
+        let by_other = queue_word(&state, &mut s, target, bob, "crane", None).unwrap_err();
+        assert_eq!(
+            by_other.to_string(),
+            "Already queued by <@7>, at position 2 of 2."
+        );
+        let by_self = queue_word(&state, &mut s, target, alice, "crane", None).unwrap_err();
+        assert_eq!(
+            by_self.to_string(),
+            "Already queued by you, at position 2 of 2."
+        );
+    }
+
+    #[tokio::test]
+    async fn scheduled_runs_are_spaced_out() {
+        let spacing = std::time::Duration::from_millis(20);
+        let started = std::time::Instant::now();
+        let mut seen = Vec::new();
+        let failing = run_spaced([1, 2, 3], spacing, |i| {
+            seen.push((i, started.elapsed()));
+            async move { i == 2 }
+        })
+        .await;
+        assert_eq!(failing, [2]);
+        assert_eq!(seen.iter().map(|&(i, _)| i).collect::<Vec<_>>(), [1, 2, 3]);
+        // no wait before the first, one between each after
+        assert!(seen[0].1 < spacing);
+        assert!(seen[2].1 >= spacing * 2);
+    }
+
+    #[test]
+    fn skipped_weekend_moves_the_target_to_monday() {
+        let mut state = test_state(&["crane"]);
+        state.skip_weekdays = Arc::new([chrono::Weekday::Sat, chrono::Weekday::Sun].into());
+        let friday = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        assert_eq!(state.target_date_from(friday), monday);
+        // a day that isn't skipped is left alone
+        let thursday = friday.pred_opt().unwrap();
+        assert_eq!(state.target_date_from(thursday), friday);
+    }
+
+    #[test]
+    fn replay_reconstructs_seeded_picks() {
+        let state = test_state(&["crane", "slate", "adieu", "fjord", "pious", "qwert"]);
+        let guild = test_guild("replay");
+        let first = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let days: Vec<NaiveDate> = first.iter_days().take(4).collect();
+        for &date in &days {
+            select_word(&state, &guild, date, &mut date_rng(date)).unwrap();
+        }
+
+        guild.store.with(|s| {
+            for &date in &days {
+                let recorded = s.history.iter().find(|e| e.date == date).unwrap();
+                assert_eq!(
+                    replay_pick(&state, s, date).as_deref(),
+                    Some(&*recorded.word)
+                );
+                // the rolled back state only has the days before
+                assert!(s.as_of(date).history.iter().all(|e| e.date < date));
+            }
+        });
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_cap_then_stops() {
+        let mut rng = rand::rng();
+        let mut within = |attempt, secs: f64| {
+            let d = retry_delay(attempt, &mut rng).unwrap().as_secs_f64();
+            assert!(
+                (secs * 0.8..=secs * 1.2).contains(&d),
+                "attempt {attempt}: {d}s"
+            );
+        };
+        within(0, 15.0);
+        within(1, 30.0);
+        within(2, 60.0);
+        within(3, 120.0);
+        within(4, 120.0);
+        assert!(retry_delay(5, &mut rand::rng()).is_none());
+    }
+
+    #[test]
+    fn queue_aging_puts_passed_over_entries_first() {
+        let queued = |user, word: &str, skipped| QueuedWord {
+            user: UserId::new(user),
+            word: word.to_owned(),
+            note: None,
+            skipped,
+        };
+        let queue: std::collections::VecDeque<QueuedWord> = [
+            queued(1, "crane", 0),
+            queued(2, "slate", 2),
+            queued(3, "adieu", 0),
+            queued(4, "fjord", 2),
+        ]
+        .into();
+
+        let mut state = test_state(&["crane"]);
+        assert_eq!(queue_order(&state, &queue), [0, 1, 2, 3]);
+        state.queue_aging = true;
+        // ties keep their place in the queue
+        assert_eq!(queue_order(&state, &queue), [1, 3, 0, 2]);
+    }
+
+    #[test]
+    fn entries_passed_over_for_cooldown_age() {
+        let mut state = test_state(&["crane", "slate", "adieu"]);
+        state.suggester_cooldown_days = 7;
+        state.queue_aging = true;
+        let guild = test_guild("queue-aging");
+        let target = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let (alice, bob) = (UserId::new(7), UserId::new(8));
+        guild.store.with_mut(|s| {
+            s.mark_used(
+                target - chrono::Duration::days(2),
+                "adieu".to_owned(),
+                PickSource::Queue(alice),
+            );
+            for (user, word) in [(alice, "crane"), (bob, "slate")] {
+                s.queue.push_back(QueuedWord {
+                    user,
+                    word: word.to_owned(),
+                    note: None,
+                    skipped: 0,
+                });
+            }
+        });
+
+        assert_eq!(
+            take_from_queue(&state, &guild, target),
+            Some(("slate".to_owned(), bob))
+        );
+        guild.store.with(|s| {
+            assert_eq!(s.queue.len(), 1);
+            assert_eq!(s.queue[0].word, "crane");
+            assert_eq!(s.queue[0].skipped, 1);
+        });
+    }
+
+    #[test]
+    fn eligible_pool_leaves_out_used_words_and_weighs_recent_letters() {
+        let mut state = test_state(&["crane", "fizzy", "jazzy", "slate"]);
+        state.recent_letter_window = 1;
+        state.recent_letter_penalty = 0.5;
+        let target = NaiveDate::from_ymd_opt(2024, 3, 2).unwrap();
+        let mut s = BotState::default();
+        s.mark_used(
+            target.pred_opt().unwrap(),
+            "jazzy".to_owned(),
+            PickSource::Weighted,
+        );
+
+        let pool = eligible_pool(&state, &s, target);
+        let (keys, dist) = pool.weighted().unwrap();
+        assert_eq!(keys, ["crane", "fizzy", "slate"]);
+        // fizzy shares z and y with yesterday's word
+        let weights: Vec<f64> = dist.weights().collect();
+        let unpenalised = (pool.dict["fizzy"].max(0.0) + 1e-6).powf(SAMPLE_ALPHA);
+        assert!((weights[1] - unpenalised * 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn puzzle_dates_step_over_skipped_weekends() {
+        let mut state = test_state(&["crane"]);
+        state.skip_weekdays = Arc::new([chrono::Weekday::Sat, chrono::Weekday::Sun].into());
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        // Thursday the 29th of February on, the 2nd and 3rd are a weekend
+        let first = day(1).pred_opt().unwrap();
+        assert_eq!(
+            state.puzzle_dates(first, 4),
+            [first, day(1), day(4), day(5)]
+        );
+        // starting on a skipped day moves to the Monday
+        assert_eq!(state.puzzle_dates(day(2), 1), [day(4)]);
+    }
+
+    #[test]
+    fn digest_leaves_out_words_not_played_yet() {
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let mut s = BotState::default();
+        // with a lead of two days, the 11th and 12th are already announced
+        for (d, w) in [
+            (3, "adieu"),
+            (4, "crane"),
+            (9, "slate"),
+            (10, "fjord"),
+            (11, "pious"),
+            (12, "qwert"),
+        ] {
+            s.mark_used(day(d), w.to_owned(), PickSource::Weighted);
+            s.mark_announced(day(d), None);
+        }
+        let week: Vec<_> = digest_week(&s, day(10))
+            .into_iter()
+            .map(|(d, w, _)| (d, w))
+            .collect();
+        assert_eq!(
+            week,
+            [
+                (day(4), "crane".to_owned()),
+                (day(9), "slate".to_owned()),
+                (day(10), "fjord".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn announce_time_follows_the_lead() {
+        let tz = Tz::UTC;
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let at = |h, m, s| tz.with_ymd_and_hms(2024, 3, 1, h, m, s).unwrap();
+        assert_eq!(announce_time(&tz, day, 1, 0), at(23, 55, 0));
+        assert_eq!(announce_time(&tz, day, 2, 0), at(23, 55, 0));
+        // today's word goes out as the day starts
+        assert_eq!(announce_time(&tz, day, 0, 0), at(0, 0, 0));
+        // jitter never leaves the day
+        assert_eq!(announce_time(&tz, day, 0, -10), at(0, 0, 0));
+        assert_eq!(announce_time(&tz, day, 0, 10), at(0, 10, 0));
+        assert_eq!(announce_time(&tz, day, 1, 10), at(23, 59, 59));
+    }
+
+    #[test]
+    fn announce_time_survives_a_dst_jump_at_midnight() {
+        // Santiago's clocks went from 00:00 to 01:00 on 2023-09-03
+        let tz: Tz = "America/Santiago".parse().unwrap();
+        let day = NaiveDate::from_ymd_opt(2023, 9, 3).unwrap();
+        let t = announce_time(&tz, day, 0, 0);
+        assert_eq!(t.date_naive(), day);
+        assert_eq!(t.time(), chrono::NaiveTime::from_hms_opt(1, 0, 0).unwrap());
+    }
+
+    #[tokio::test]
+    async fn rerun_without_a_message_doesnt_post_again() {
+        let state = test_state(&["crane", "slate"]);
+        let guild = test_guild("rerun_without_message");
+        let target = state.target_date();
+        guild.store.with_mut(|s| {
+            s.mark_used(target, "crane".into(), PickSource::Weighted);
+            s.mark_announced(target, None);
+        });
+        let sink = RecordingSink::default();
+
+        let outcome = run_once(&sink, &NoNames, &state, &guild, false).await;
+        assert!(
+            matches!(outcome, Ok(RunOutcome::AlreadyAnnounced(d, ref w)) if d == target && w == "crane")
+        );
+        assert!(sink.announced.lock().is_empty());
+        assert!(sink.edited.lock().is_empty());
+    }
+
+    #[test]
+    fn schedule_rejects_skipped_weekdays() {
+        let mut state = test_state(&["crane", "slate"]);
+        let skipped = state.target_date() + chrono::Duration::days(8);
+        state.skip_weekdays = Arc::new([skipped.weekday()].into());
+        let s = BotState::default();
+
+        let err = schedule_problem(&state, &s, skipped, "slate").unwrap_err();
+        assert!(err.contains("no puzzle"), "{err}");
+        assert!(schedule_problem(&state, &s, skipped + chrono::Duration::days(1), "slate").is_ok());
+    }
+
+    #[test]
+    fn health_reads_the_whole_state_file() {
+        let path =
+            std::env::temp_dir().join(format!("starting-word-health-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        assert_eq!(state_file_problem(&path), None);
+
+        fs::write(&path, serde_json::to_vec(&BotState::default()).unwrap()).unwrap();
+        assert_eq!(state_file_problem(&path), None);
+
+        fs::write(&path, b"{\"history\": [").unwrap();
+        let problem = state_file_problem(&path).unwrap();
+        assert!(problem.starts_with("state file corrupt"), "{problem}");
+        fs::remove_file(&path).unwrap();
+    }
 }
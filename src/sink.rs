@@ -0,0 +1,602 @@
+use chrono::{Datelike, NaiveDate};
+use serenity::all::{
+    ChannelId, ChannelType, Context, CreateAllowedMentions, CreateMessage, CreateThread,
+    EditMessage, EditWebhookMessage, ExecuteWebhook, Message, UserId, Webhook,
+};
+use tracing::{error, info, warn};
+
+use crate::{AppState, GuildState, error::SelectError, state::PostedMessage};
+
+/// Where a server's announcements end up, kept apart from selection so picking a word doesn't
+/// need a live client
+#[allow(async_fn_in_trait)] // only implemented and called within this crate
+pub trait AnnounceSink {
+    /// Returns the posted message, if the sink posts one that can be edited later
+    async fn announce(
+        &self,
+        date: NaiveDate,
+        word: &str,
+        suggested_by: Option<&str>,
+    ) -> Result<Option<PostedMessage>, SelectError>;
+
+    /// `announce`, showing the word a letter at a time where the sink can edit its post. The
+    /// others post the word straight away
+    async fn announce_slowly(
+        &self,
+        date: NaiveDate,
+        word: &str,
+        suggested_by: Option<&str>,
+    ) -> Result<Option<PostedMessage>, SelectError> {
+        self.announce(date, word, suggested_by).await
+    }
+
+    /// Rewrites an earlier announcement in place, editing doesn't ping anyone
+    async fn edit(
+        &self,
+        message: PostedMessage,
+        date: NaiveDate,
+        word: &str,
+        suggested_by: Option<&str>,
+    ) -> Result<(), SelectError>;
+
+    /// Shows the word for a day that's over, without spoilers
+    async fn reveal(&self, date: NaiveDate, word: &str) -> Result<(), SelectError>;
+
+    /// Tells the server no word could be picked for `date`
+    async fn no_word(&self, date: NaiveDate, message: &str) -> Result<(), SelectError>;
+
+    /// Posts the weekly recap, without pinging anyone it mentions
+    async fn digest(&self, text: &str) -> Result<(), SelectError>;
+
+    /// Gives moderators a heads-up in the mod channel, if the server has one
+    async fn mod_notice(&self, text: &str) -> Result<(), SelectError>;
+}
+
+/// How suggesters are named when credited, kept apart from the sink for the same reason
+#[allow(async_fn_in_trait)]
+pub trait MemberNames {
+    /// `user`'s server nickname, or `None` if they can't be looked up
+    async fn display_name(&self, guild: &GuildState, user: UserId) -> Option<String>;
+}
+
+impl MemberNames for Context {
+    async fn display_name(&self, guild: &GuildState, user: UserId) -> Option<String> {
+        match guild.display_name(self, user).await {
+            Ok(name) => Some(name),
+            Err(e) => {
+                warn!("Couldn't look up member {user}: {e}");
+                None
+            }
+        }
+    }
+}
+
+/// Posts to the announce channel, or its thread if one is configured
+pub struct DiscordSink<'a> {
+    pub ctx: &'a Context,
+    pub state: &'a AppState,
+    pub guild: &'a GuildState,
+}
+
+/// Posts through the server's `WEBHOOK_URL`, under the webhook's own name and avatar
+pub struct WebhookSink<'a> {
+    pub ctx: &'a Context,
+    pub state: &'a AppState,
+    pub guild: &'a GuildState,
+    pub url: &'a str,
+}
+
+/// Logs what would have been posted, for `DRY_RUN`
+pub struct LogSink<'a> {
+    pub state: &'a AppState,
+    pub guild: &'a GuildState,
+}
+
+/// Whichever sink the settings call for: a dry run logs, a webhook URL wins over the bot
+pub enum ConfiguredSink<'a> {
+    Discord(DiscordSink<'a>),
+    Webhook(WebhookSink<'a>),
+    Log(LogSink<'a>),
+}
+
+impl<'a> ConfiguredSink<'a> {
+    pub fn new(ctx: &'a Context, state: &'a AppState, guild: &'a GuildState) -> Self {
+        if state.dry_run {
+            Self::Log(LogSink { state, guild })
+        } else if let Some(url) = guild
+            .webhook_url
+            .as_ref()
+            .filter(|_| guild.test_channel_id.is_none())
+        {
+            Self::Webhook(WebhookSink {
+                ctx,
+                state,
+                guild,
+                url,
+            })
+        } else {
+            Self::Discord(DiscordSink { ctx, state, guild })
+        }
+    }
+}
+
+impl AnnounceSink for ConfiguredSink<'_> {
+    async fn announce(
+        &self,
+        date: NaiveDate,
+        word: &str,
+        suggested_by: Option<&str>,
+    ) -> Result<Option<PostedMessage>, SelectError> {
+        match self {
+            Self::Discord(s) => s.announce(date, word, suggested_by).await,
+            Self::Webhook(s) => s.announce(date, word, suggested_by).await,
+            Self::Log(s) => s.announce(date, word, suggested_by).await,
+        }
+    }
+
+    async fn announce_slowly(
+        &self,
+        date: NaiveDate,
+        word: &str,
+        suggested_by: Option<&str>,
+    ) -> Result<Option<PostedMessage>, SelectError> {
+        match self {
+            Self::Discord(s) => s.announce_slowly(date, word, suggested_by).await,
+            Self::Webhook(s) => s.announce_slowly(date, word, suggested_by).await,
+            Self::Log(s) => s.announce_slowly(date, word, suggested_by).await,
+        }
+    }
+
+    async fn edit(
+        &self,
+        message: PostedMessage,
+        date: NaiveDate,
+        word: &str,
+        suggested_by: Option<&str>,
+    ) -> Result<(), SelectError> {
+        match self {
+            Self::Discord(s) => s.edit(message, date, word, suggested_by).await,
+            Self::Webhook(s) => s.edit(message, date, word, suggested_by).await,
+            Self::Log(s) => s.edit(message, date, word, suggested_by).await,
+        }
+    }
+
+    async fn reveal(&self, date: NaiveDate, word: &str) -> Result<(), SelectError> {
+        match self {
+            Self::Discord(s) => s.reveal(date, word).await,
+            Self::Webhook(s) => s.reveal(date, word).await,
+            Self::Log(s) => s.reveal(date, word).await,
+        }
+    }
+
+    async fn no_word(&self, date: NaiveDate, message: &str) -> Result<(), SelectError> {
+        match self {
+            Self::Discord(s) => s.no_word(date, message).await,
+            Self::Webhook(s) => s.no_word(date, message).await,
+            Self::Log(s) => s.no_word(date, message).await,
+        }
+    }
+
+    async fn digest(&self, text: &str) -> Result<(), SelectError> {
+        match self {
+            Self::Discord(s) => s.digest(text).await,
+            Self::Webhook(s) => s.digest(text).await,
+            Self::Log(s) => s.digest(text).await,
+        }
+    }
+
+    async fn mod_notice(&self, text: &str) -> Result<(), SelectError> {
+        match self {
+            Self::Discord(s) => s.mod_notice(text).await,
+            Self::Webhook(s) => s.mod_notice(text).await,
+            Self::Log(s) => s.mod_notice(text).await,
+        }
+    }
+}
+
+impl AnnounceSink for DiscordSink<'_> {
+    async fn announce(
+        &self,
+        date: NaiveDate,
+        word: &str,
+        suggested_by: Option<&str>,
+    ) -> Result<Option<PostedMessage>, SelectError> {
+        let msg = announcement_text(self.state, self.guild, date, word, suggested_by);
+        let mut create = CreateMessage::new().content(msg);
+        if let Some(mentions) = quiet_mentions(self.state, self.guild) {
+            create = create.allowed_mentions(mentions);
+        }
+        let message = self
+            .channel()
+            .await?
+            .send_message(&self.ctx.http, create)
+            .await?;
+        add_reactions(self.ctx, self.state, &message).await;
+        Ok(Some(PostedMessage {
+            channel_id: message.channel_id,
+            message_id: message.id,
+        }))
+    }
+
+    /// Posts the announcement with the word hidden, then edits in one letter a second. The
+    /// message is still returned if an edit fails, so a re-run can fix it up
+    async fn announce_slowly(
+        &self,
+        date: NaiveDate,
+        word: &str,
+        suggested_by: Option<&str>,
+    ) -> Result<Option<PostedMessage>, SelectError> {
+        let letters: Vec<char> = word.chars().collect();
+        let masked = |shown: usize| -> String {
+            (0..letters.len())
+                .map(|i| if i < shown { letters[i] } else { '_' })
+                .collect()
+        };
+        let msg = announcement_text(self.state, self.guild, date, &masked(0), suggested_by);
+        let mut create = CreateMessage::new().content(msg);
+        if let Some(mentions) = quiet_mentions(self.state, self.guild) {
+            create = create.allowed_mentions(mentions);
+        }
+        let message = self
+            .channel()
+            .await?
+            .send_message(&self.ctx.http, create)
+            .await?;
+        let posted = PostedMessage {
+            channel_id: message.channel_id,
+            message_id: message.id,
+        };
+
+        for shown in 1..=letters.len() {
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+            let partial = masked(shown);
+            let msg = announcement_text(self.state, self.guild, date, &partial, suggested_by);
+            let edited = message
+                .channel_id
+                .edit_message(&self.ctx.http, message.id, EditMessage::new().content(msg))
+                .await;
+            if let Err(e) = edited {
+                warn!("Slow reveal edit failed, showing the whole word: {e}");
+                if let Err(e) = self.edit(posted, date, word, suggested_by).await {
+                    error!(
+                        "Couldn't finish the slow reveal of announcement {}: {e}",
+                        message.id
+                    );
+                }
+                break;
+            }
+        }
+        add_reactions(self.ctx, self.state, &message).await;
+        Ok(Some(posted))
+    }
+
+    async fn edit(
+        &self,
+        message: PostedMessage,
+        date: NaiveDate,
+        word: &str,
+        suggested_by: Option<&str>,
+    ) -> Result<(), SelectError> {
+        let msg = announcement_text(self.state, self.guild, date, word, suggested_by);
+        message
+            .channel_id
+            .edit_message(
+                &self.ctx.http,
+                message.message_id,
+                EditMessage::new().content(msg),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn reveal(&self, date: NaiveDate, word: &str) -> Result<(), SelectError> {
+        let msg = reveal_text(self.state, date, word);
+        self.channel().await?.say(&self.ctx.http, msg).await?;
+        Ok(())
+    }
+
+    async fn no_word(&self, _date: NaiveDate, message: &str) -> Result<(), SelectError> {
+        self.channel().await?.say(&self.ctx.http, message).await?;
+        Ok(())
+    }
+
+    async fn digest(&self, text: &str) -> Result<(), SelectError> {
+        let create = CreateMessage::new()
+            .content(text)
+            .allowed_mentions(CreateAllowedMentions::new());
+        self.channel()
+            .await?
+            .send_message(&self.ctx.http, create)
+            .await?;
+        Ok(())
+    }
+
+    async fn mod_notice(&self, text: &str) -> Result<(), SelectError> {
+        post_mod_notice(self.ctx, self.guild, text).await
+    }
+}
+
+impl DiscordSink<'_> {
+    async fn channel(&self) -> Result<ChannelId, serenity::Error> {
+        // the saved thread is under the real announce channel, so test posts skip it
+        match &self.state.announce_thread_name {
+            Some(name) if self.guild.test_channel_id.is_none() => self.thread(name).await,
+            _ => Ok(self.guild.announce_channel()),
+        }
+    }
+
+    /// The announcement thread under the announce channel, recreated if it was archived or deleted
+    async fn thread(&self, name: &str) -> Result<ChannelId, serenity::Error> {
+        let guild = self.guild;
+        if let Some(id) = guild.store.with(|s| s.thread_id) {
+            match id.to_channel(self.ctx).await {
+                Ok(channel) => {
+                    let archived = channel
+                        .guild()
+                        .and_then(|c| c.thread_metadata)
+                        .is_some_and(|m| m.archived);
+                    if !archived {
+                        return Ok(id);
+                    }
+                    info!("Announcement thread {id} was archived, creating a new one");
+                }
+                Err(e) => warn!("Announcement thread {id} is gone ({e}), creating a new one"),
+            }
+        }
+        let thread = guild
+            .announce_channel()
+            .create_thread(
+                &self.ctx.http,
+                CreateThread::new(name).kind(ChannelType::PublicThread),
+            )
+            .await?;
+        guild.store.with_mut(|s| s.thread_id = Some(thread.id));
+        Ok(thread.id)
+    }
+}
+
+impl WebhookSink<'_> {
+    async fn webhook(&self) -> Result<Webhook, serenity::Error> {
+        Webhook::from_url(&self.ctx.http, self.url).await
+    }
+
+    async fn post(&self, builder: ExecuteWebhook) -> Result<Message, SelectError> {
+        let builder = match &self.state.webhook_name {
+            Some(name) => builder.username(name),
+            None => builder,
+        };
+        let builder = match &self.state.webhook_avatar_url {
+            Some(url) => builder.avatar_url(url),
+            None => builder,
+        };
+        self.webhook()
+            .await?
+            .execute(&self.ctx.http, true, builder)
+            .await?
+            .ok_or(SelectError::WebhookNoMessage)
+    }
+}
+
+impl AnnounceSink for WebhookSink<'_> {
+    async fn announce(
+        &self,
+        date: NaiveDate,
+        word: &str,
+        suggested_by: Option<&str>,
+    ) -> Result<Option<PostedMessage>, SelectError> {
+        let msg = announcement_text(self.state, self.guild, date, word, suggested_by);
+        let mut builder = ExecuteWebhook::new().content(msg);
+        if let Some(mentions) = quiet_mentions(self.state, self.guild) {
+            builder = builder.allowed_mentions(mentions);
+        }
+        let message = self.post(builder).await?;
+        add_reactions(self.ctx, self.state, &message).await;
+        Ok(Some(PostedMessage {
+            channel_id: message.channel_id,
+            message_id: message.id,
+        }))
+    }
+
+    async fn edit(
+        &self,
+        message: PostedMessage,
+        date: NaiveDate,
+        word: &str,
+        suggested_by: Option<&str>,
+    ) -> Result<(), SelectError> {
+        let msg = announcement_text(self.state, self.guild, date, word, suggested_by);
+        self.webhook()
+            .await?
+            .edit_message(
+                &self.ctx.http,
+                message.message_id,
+                EditWebhookMessage::new().content(msg),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn reveal(&self, date: NaiveDate, word: &str) -> Result<(), SelectError> {
+        let msg = reveal_text(self.state, date, word);
+        self.post(ExecuteWebhook::new().content(msg)).await?;
+        Ok(())
+    }
+
+    async fn no_word(&self, _date: NaiveDate, message: &str) -> Result<(), SelectError> {
+        self.post(ExecuteWebhook::new().content(message)).await?;
+        Ok(())
+    }
+
+    async fn digest(&self, text: &str) -> Result<(), SelectError> {
+        let builder = ExecuteWebhook::new()
+            .content(text)
+            .allowed_mentions(CreateAllowedMentions::new());
+        self.post(builder).await?;
+        Ok(())
+    }
+
+    /// Heads-ups come from the bot itself, the webhook only speaks for announcements
+    async fn mod_notice(&self, text: &str) -> Result<(), SelectError> {
+        post_mod_notice(self.ctx, self.guild, text).await
+    }
+}
+
+impl AnnounceSink for LogSink<'_> {
+    async fn announce(
+        &self,
+        date: NaiveDate,
+        word: &str,
+        suggested_by: Option<&str>,
+    ) -> Result<Option<PostedMessage>, SelectError> {
+        let msg = announcement_text(self.state, self.guild, date, word, suggested_by);
+        info!(
+            "[dry run] would announce in {}:\n{msg}",
+            self.guild.announce_channel()
+        );
+        Ok(None)
+    }
+
+    async fn edit(
+        &self,
+        message: PostedMessage,
+        _date: NaiveDate,
+        _word: &str,
+        _suggested_by: Option<&str>,
+    ) -> Result<(), SelectError> {
+        info!("[dry run] would edit announcement {}", message.message_id);
+        Ok(())
+    }
+
+    async fn reveal(&self, date: NaiveDate, word: &str) -> Result<(), SelectError> {
+        let msg = reveal_text(self.state, date, word);
+        info!(
+            "[dry run] would reveal in {}: {msg}",
+            self.guild.announce_channel()
+        );
+        Ok(())
+    }
+
+    async fn no_word(&self, date: NaiveDate, message: &str) -> Result<(), SelectError> {
+        info!(
+            "[dry run] would post in {} that {date} has no word: {message}",
+            self.guild.announce_channel()
+        );
+        Ok(())
+    }
+
+    async fn digest(&self, text: &str) -> Result<(), SelectError> {
+        info!(
+            "[dry run] would post the weekly digest in {}:\n{text}",
+            self.guild.announce_channel()
+        );
+        Ok(())
+    }
+
+    async fn mod_notice(&self, text: &str) -> Result<(), SelectError> {
+        if let Some(channel_id) = self.guild.mod_channel_id {
+            info!("[dry run] would post in {channel_id}: {text}");
+        }
+        Ok(())
+    }
+}
+
+/// Posts `text` to the server's mod channel, if it has one
+async fn post_mod_notice(ctx: &Context, guild: &GuildState, text: &str) -> Result<(), SelectError> {
+    if let Some(channel_id) = guild.mod_channel_id {
+        channel_id.say(&ctx.http, text).await?;
+    }
+    Ok(())
+}
+
+/// With `QUIET_CREDIT` the suggester is still shown as a mention, but only the role is notified
+fn quiet_mentions(state: &AppState, guild: &GuildState) -> Option<CreateAllowedMentions> {
+    state
+        .quiet_credit
+        .then(|| CreateAllowedMentions::new().roles(guild.role_id.into_iter().collect::<Vec<_>>()))
+}
+
+/// A custom emoji from a server the bot isn't in can't be used, that shouldn't fail the
+/// announcement itself
+async fn add_reactions(ctx: &Context, state: &AppState, message: &Message) {
+    for emoji in state.announce_reactions.iter() {
+        if let Err(e) = message.react(&ctx.http, emoji.clone()).await {
+            warn!(
+                "Failed to react with {emoji} to announcement {}: {e}",
+                message.id
+            );
+        }
+    }
+}
+
+fn announcement_text(
+    state: &AppState,
+    guild: &GuildState,
+    date: NaiveDate,
+    word: &str,
+    suggested_by: Option<&str>,
+) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    if let Some(m) = suggested_by {
+        parts.push(format!("Suggested by {m}"));
+    }
+    if state.show_streak {
+        let streak = guild
+            .store
+            .with(|s| s.streak(date, |d| state.skip_weekdays.contains(&d.weekday())));
+        if streak > 1 {
+            parts.push(format!("🔥 {streak} days in a row"));
+        }
+    }
+
+    let suffix = if parts.is_empty() {
+        String::new()
+    } else {
+        parts.join("\n").to_string()
+    };
+    let word = if state.spoiler {
+        format!("||`{word}`||")
+    } else {
+        format!("`{word}`")
+    };
+    let day = day_label(state.today(), date);
+    let ping = guild
+        .role_id
+        .map(|role| format!("<@&{role}>\n"))
+        .unwrap_or_default();
+    format!(
+        "{ping}{day} Wordle starter ({}) is: {word}\n{suffix}",
+        date.format(&state.date_format)
+    )
+}
+
+/// How the announcement refers to `date`. Counted from today rather than `ANNOUNCE_LEAD_DAYS`,
+/// which skipped weekdays can push the target past
+fn day_label(today: NaiveDate, date: NaiveDate) -> &'static str {
+    match (date - today).num_days() {
+        0 => "Today’s",
+        1 => "Tomorrow’s",
+        _ => "Upcoming",
+    }
+}
+
+fn reveal_text(state: &AppState, date: NaiveDate, word: &str) -> String {
+    format!(
+        "The starter word for {} was: `{word}`",
+        date.format(&state.date_format)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_label_counts_from_today() {
+        let friday = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        assert_eq!(day_label(friday, friday), "Today’s");
+        assert_eq!(day_label(friday, friday.succ_opt().unwrap()), "Tomorrow’s");
+        // Saturday and Sunday skipped, Monday's word goes out on Friday night
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        assert_eq!(day_label(friday, monday), "Upcoming");
+    }
+}
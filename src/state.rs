@@ -1,5 +1,5 @@
 use std::{
-    collections::{HashSet, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     fs,
     io::Write,
     path::Path,
@@ -9,23 +9,278 @@ use anyhow::Context;
 use chrono::NaiveDate;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use serenity::all::UserId;
+use serenity::all::{ChannelId, MessageId, UserId};
+use tracing::error;
 
+/// Older state files also stored a `used` set, it's now derived from `history` and the stored
+/// copy is ignored when loading
 #[derive(Serialize, Deserialize, Default, Clone)]
 pub struct BotState {
-    pub used: HashSet<String>,
     pub history: Vec<UsedEntry>,
-    pub queue: VecDeque<(UserId, String)>,
+    pub queue: VecDeque<QueuedWord>,
+    #[serde(default)]
+    pub scheduled: HashMap<NaiveDate, String>,
+    /// Set by `/setchannel`, replaces the configured announce channel
+    #[serde(default)]
+    pub announce_channel_id: Option<ChannelId>,
+    /// Thread announcements are posted in, when `ANNOUNCE_THREAD_NAME` is set
+    #[serde(default)]
+    pub thread_id: Option<ChannelId>,
+    /// Set by `/pause`, the scheduler skips this server until `/resume`
+    #[serde(default)]
+    pub paused: bool,
+    #[serde(default)]
+    pub rejections: RejectionCounts,
+    /// Used words members suggested again, with `REUSE_REQUESTS` on
+    #[serde(default)]
+    pub reuse_requests: Vec<ReuseRequest>,
+    /// The day the weekly digest was last posted, so a restart doesn't post it twice
+    #[serde(default)]
+    pub last_digest: Option<NaiveDate>,
+    /// The last date of each word moved out of `history` by `Store::archive_before`, so they
+    /// still count as used
+    #[serde(default)]
+    pub archived_words: HashMap<String, NaiveDate>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReuseRequest {
+    pub user: UserId,
+    pub word: String,
+    /// The first date the word can be picked again
+    pub eligible: NaiveDate,
+}
+
+/// A `/suggest`ed word waiting for its day. Older state files stored `[user, word]` pairs, those
+/// still load
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(from = "QueuedWordRepr")]
+pub struct QueuedWord {
+    pub user: UserId,
+    pub word: String,
+    /// Shown with the credit when the word is announced, already sanitized
+    pub note: Option<String>,
+    /// How many times a later entry was picked ahead of this one, for `QUEUE_AGING`
+    pub skipped: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum QueuedWordRepr {
+    Pair(UserId, String),
+    Entry {
+        user: UserId,
+        word: String,
+        #[serde(default)]
+        note: Option<String>,
+        #[serde(default)]
+        skipped: u32,
+    },
+}
+
+impl From<QueuedWordRepr> for QueuedWord {
+    fn from(r: QueuedWordRepr) -> Self {
+        match r {
+            QueuedWordRepr::Pair(user, word) => Self {
+                user,
+                word,
+                note: None,
+                skipped: 0,
+            },
+            QueuedWordRepr::Entry {
+                user,
+                word,
+                note,
+                skipped,
+            } => Self {
+                user,
+                word,
+                note,
+                skipped,
+            },
+        }
+    }
+}
+
+/// How many `/suggest` words were turned down, by reason
+#[derive(Serialize, Deserialize, Default, Clone, Copy)]
+#[serde(default)]
+pub struct RejectionCounts {
+    pub lookalike: u64,
+    pub bad_letters: u64,
+    pub not_in_dictionary: u64,
+    pub already_used: u64,
+    pub already_queued: u64,
+    pub queue_cap: u64,
 }
 
 impl BotState {
-    pub fn mark_used(&mut self, date: NaiveDate, word: String, suggested_by: Option<UserId>) {
-        self.used.insert(word.clone());
+    /// Records `word` for `date`, returning false (and changing nothing) if `date` already has one
+    pub fn mark_used(&mut self, date: NaiveDate, word: String, source: PickSource) -> bool {
+        if self.history.iter().any(|e| e.date == date) {
+            return false;
+        }
         self.history.push(UsedEntry {
             date,
             word,
-            suggested_by,
+            suggested_by: match source {
+                PickSource::Queue(user) => Some(user),
+                PickSource::Scheduled | PickSource::Weighted => None,
+            },
+            announced: false,
+            revealed: false,
+            note: None,
+            message: None,
+            source: Some(source),
         });
+        true
+    }
+
+    pub fn mark_announced(&mut self, date: NaiveDate, message: Option<PostedMessage>) {
+        if let Some(e) = self.history.iter_mut().rev().find(|e| e.date == date) {
+            e.announced = true;
+            e.message = message.or(e.message);
+        }
+    }
+
+    /// The word whose most recent use is the oldest, limited to words accepted by `is_valid`
+    pub fn least_recently_used(&self, is_valid: impl Fn(&str) -> bool) -> Option<String> {
+        self.last_used()
+            .into_iter()
+            .filter(|(w, _)| is_valid(w))
+            .min_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)))
+            .map(|(w, _)| w.to_owned())
+    }
+
+    /// The most recent date of every used word, archived ones included
+    pub fn last_used(&self) -> HashMap<&str, NaiveDate> {
+        let mut last_used: HashMap<&str, NaiveDate> = self
+            .archived_words
+            .iter()
+            .map(|(w, d)| (w.as_str(), *d))
+            .collect();
+        for e in &self.history {
+            let d = last_used.entry(e.word.as_str()).or_insert(e.date);
+            *d = (*d).max(e.date);
+        }
+        last_used
+    }
+
+    /// Whether `user` had a suggestion accepted within `days` days before `target`
+    pub fn suggester_on_cooldown(&self, user: UserId, target: NaiveDate, days: i64) -> bool {
+        if days <= 0 {
+            return false;
+        }
+        self.history
+            .iter()
+            .filter(|e| e.suggested_by == Some(user) && e.date < target)
+            .map(|e| e.date)
+            .max()
+            .is_some_and(|last| (target - last).num_days() <= days)
+    }
+
+    /// The `n` most recently dated words, newest first
+    pub fn recent_words(&self, n: usize) -> Vec<String> {
+        let mut entries: Vec<&UsedEntry> = self.history.iter().collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.date));
+        entries
+            .into_iter()
+            .take(n)
+            .map(|e| e.word.clone())
+            .collect()
+    }
+
+    /// A copy with only the history and archived words from before `date`, as it would have
+    /// looked that morning
+    pub fn as_of(&self, date: NaiveDate) -> BotState {
+        BotState {
+            history: self
+                .history
+                .iter()
+                .filter(|e| e.date < date)
+                .cloned()
+                .collect(),
+            archived_words: self
+                .archived_words
+                .iter()
+                .filter(|(_, d)| **d < date)
+                .map(|(w, d)| (w.clone(), *d))
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// How many days in a row up to and including `date` have a word, not counting days
+    /// `skipped` says have no puzzle
+    pub fn streak(&self, date: NaiveDate, skipped: impl Fn(NaiveDate) -> bool) -> usize {
+        let dates: HashSet<NaiveDate> = self.history.iter().map(|e| e.date).collect();
+        let mut day = date;
+        let mut streak = 0;
+        loop {
+            if !skipped(day) {
+                if !dates.contains(&day) {
+                    return streak;
+                }
+                streak += 1;
+            }
+            day -= chrono::Duration::days(1);
+        }
+    }
+
+    /// Notes that `user` wants `word` back, returning when it can next be picked for a target
+    /// after `target` with a `days` long reuse window
+    pub fn request_reuse(
+        &mut self,
+        user: UserId,
+        word: &str,
+        target: NaiveDate,
+        days: i64,
+    ) -> Option<NaiveDate> {
+        let last = self
+            .history
+            .iter()
+            .filter(|e| e.word == word)
+            .map(|e| e.date)
+            .max()?;
+        let eligible = (last + chrono::Duration::days(days + 1)).max(target);
+        if !self
+            .reuse_requests
+            .iter()
+            .any(|r| r.user == user && r.word == word)
+        {
+            self.reuse_requests.push(ReuseRequest {
+                user,
+                word: word.to_owned(),
+                eligible,
+            });
+        }
+        Some(eligible)
+    }
+
+    /// Every word that has ever been announced, archived ones included
+    pub fn used(&self) -> HashSet<String> {
+        self.history
+            .iter()
+            .map(|e| &e.word)
+            .chain(self.archived_words.keys())
+            .cloned()
+            .collect()
+    }
+
+    /// Words announced on or after `since`, archived ones included
+    pub fn used_since(&self, since: NaiveDate) -> HashSet<String> {
+        let archived = self
+            .archived_words
+            .iter()
+            .filter(|(_, d)| **d >= since)
+            .map(|(w, _)| w);
+        self.history
+            .iter()
+            .filter(|e| e.date >= since)
+            .map(|e| &e.word)
+            .chain(archived)
+            .cloned()
+            .collect()
     }
 }
 
@@ -34,6 +289,54 @@ pub struct UsedEntry {
     pub date: NaiveDate,
     pub word: String,
     pub suggested_by: Option<UserId>,
+    /// Entries from before this was tracked were all announced
+    #[serde(default = "default_true")]
+    pub announced: bool,
+    /// Whether the word was posted without spoilers once its day was over, older entries are
+    /// treated as done so they're never revealed late
+    #[serde(default = "default_true")]
+    pub revealed: bool,
+    /// The suggester's note from `/suggest`
+    #[serde(default)]
+    pub note: Option<String>,
+    /// The announcement message, so a re-run edits it instead of pinging again. Missing for
+    /// entries from before this was tracked and for dry runs
+    #[serde(default)]
+    pub message: Option<PostedMessage>,
+    /// How the word was picked, missing for entries from before this was tracked
+    #[serde(default)]
+    pub source: Option<PickSource>,
+}
+
+/// Where a day's word came from
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PickSource {
+    Scheduled,
+    Queue(UserId),
+    Weighted,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct PostedMessage {
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+}
+
+/// The word history as published for other tools, `{"dates": [{"date": "2024-01-31", "word":
+/// "crane"}]}` oldest first. This is a public contract, keep it stable when `BotState` changes
+#[derive(Serialize)]
+pub struct PublicView {
+    pub dates: Vec<PublicDate>,
+}
+
+#[derive(Serialize)]
+pub struct PublicDate {
+    pub date: NaiveDate,
+    pub word: String,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 pub struct Store {
@@ -49,20 +352,43 @@ impl Store {
         }
     }
 
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// A file that can't be parsed is moved to `{path}.corrupt` and the bot starts fresh, rather
+    /// than refusing to start at all
     pub fn load(&self) -> anyhow::Result<()> {
         let p = Path::new(&self.path);
         if !p.exists() {
             return Ok(());
         }
         let bytes = fs::read(p).with_context(|| format!("reading {}", self.path))?;
-        let state: BotState = serde_json::from_slice(&bytes)?;
+        let state: BotState = match serde_json::from_slice(&bytes) {
+            Ok(state) => state,
+            Err(e) => {
+                let aside = format!("{}.corrupt", self.path);
+                fs::rename(p, &aside)
+                    .with_context(|| format!("moving corrupt {} aside", self.path))?;
+                error!(
+                    "State file {} is corrupt ({e}), moved it to {aside} and starting with empty \
+                     state. Use /restore with a backup to recover",
+                    self.path
+                );
+                BotState::default()
+            }
+        };
         *self.inner.write() = state;
         Ok(())
     }
 
+    /// The state serialized exactly as it's written to disk
+    pub fn snapshot_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec_pretty(&*self.inner.read())?)
+    }
+
     pub fn save(&self) -> anyhow::Result<()> {
-        let state = self.inner.read().clone();
-        let buffer = serde_json::to_vec_pretty(&state)?;
+        let buffer = self.snapshot_bytes()?;
 
         // Write to temp so that if writing causes the failure, it wont have altered the main save
         let tmp = format!("{}.tmp", self.path);
@@ -76,6 +402,69 @@ impl Store {
         Ok(())
     }
 
+    /// Announced words dated before `before`, so upcoming puzzles aren't given away
+    pub fn public_view(&self, before: NaiveDate) -> PublicView {
+        let mut dates: Vec<PublicDate> = self
+            .inner
+            .read()
+            .history
+            .iter()
+            .filter(|e| e.announced && e.date < before)
+            .map(|e| PublicDate {
+                date: e.date,
+                word: e.word.clone(),
+            })
+            .collect();
+        dates.sort_by_key(|d| d.date);
+        PublicView { dates }
+    }
+
+    /// Moves announced and revealed entries dated before `cutoff` out of `history`, appending them
+    /// to a gzipped NDJSON file at `archive_path`. Returns how many were moved
+    pub fn archive_before(&self, cutoff: NaiveDate, archive_path: &str) -> anyhow::Result<usize> {
+        let archivable = |e: &UsedEntry| e.date < cutoff && e.announced && e.revealed;
+        let mut state = self.inner.write();
+        let old: Vec<&UsedEntry> = state.history.iter().filter(|e| archivable(e)).collect();
+        if old.is_empty() {
+            return Ok(0);
+        }
+
+        // each run appends one more gzip member, readers handle those with `MultiGzDecoder`
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(archive_path)
+            .with_context(|| format!("opening {archive_path}"))?;
+        let mut gz = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        for &e in &old {
+            // the archive outlives `/forgetuser`, so it keeps no trace of who suggested what
+            let e = UsedEntry {
+                suggested_by: None,
+                note: None,
+                ..e.clone()
+            };
+            serde_json::to_writer(&mut gz, &e)?;
+            gz.write_all(b"\n")?;
+        }
+        gz.finish()?.sync_all()?;
+
+        let old: Vec<UsedEntry> = old.into_iter().cloned().collect();
+        state.history.retain(|e| !archivable(e));
+        for e in &old {
+            let d = state.archived_words.entry(e.word.clone()).or_insert(e.date);
+            *d = (*d).max(e.date);
+        }
+        drop(state);
+        self.save()?;
+        Ok(old.len())
+    }
+
+    /// Swaps in a whole new state and saves it straight away
+    pub fn replace(&self, state: BotState) -> anyhow::Result<()> {
+        *self.inner.write() = state;
+        self.save()
+    }
+
     pub fn with<R>(&self, f: impl FnOnce(&BotState) -> R) -> R {
         f(&self.inner.read())
     }
@@ -85,3 +474,145 @@ impl Store {
         r
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, d).unwrap()
+    }
+
+    #[test]
+    fn suggester_cooldown_counts_days_since_last_accepted() {
+        let user = UserId::new(7);
+        let mut s = BotState::default();
+        s.mark_used(day(10), "crane".to_owned(), PickSource::Queue(user));
+        s.mark_used(day(12), "slate".to_owned(), PickSource::Weighted);
+
+        assert!(s.suggester_on_cooldown(user, day(13), 3));
+        assert!(!s.suggester_on_cooldown(user, day(14), 3));
+        assert!(!s.suggester_on_cooldown(user, day(13), 0));
+        assert!(!s.suggester_on_cooldown(UserId::new(8), day(13), 3));
+    }
+
+    #[test]
+    fn used_is_history_and_archived_words() {
+        let mut s = BotState::default();
+        s.mark_used(day(1), "crane".to_owned(), PickSource::Weighted);
+        s.mark_used(
+            day(2),
+            "slate".to_owned(),
+            PickSource::Queue(UserId::new(7)),
+        );
+        s.archived_words
+            .insert("fjord".to_owned(), day(1) - chrono::Duration::days(400));
+
+        let expected: HashSet<String> = ["crane", "slate", "fjord"]
+            .into_iter()
+            .map(str::to_owned)
+            .collect();
+        assert_eq!(s.used(), expected);
+        assert_eq!(s.used_since(day(2)), HashSet::from(["slate".to_owned()]));
+    }
+
+    #[test]
+    fn corrupt_state_is_moved_aside() {
+        let path =
+            std::env::temp_dir().join(format!("starting-word-corrupt-{}.json", std::process::id()));
+        let path = path.to_str().unwrap().to_owned();
+        let aside = format!("{path}.corrupt");
+        let store = Store::new(&path);
+        // saves, so the broken file has to be written after
+        store.with_mut(|s| s.mark_used(day(1), "crane".to_owned(), PickSource::Weighted));
+        fs::write(&path, b"{\"history\": [").unwrap();
+
+        store.load().unwrap();
+        assert!(store.with(|s| s.history.is_empty()));
+        assert!(!Path::new(&path).exists());
+        assert_eq!(fs::read(&aside).unwrap(), b"{\"history\": [");
+
+        fs::remove_file(&aside).unwrap();
+    }
+
+    #[test]
+    fn public_view_shape_is_stable() {
+        let path =
+            std::env::temp_dir().join(format!("starting-word-public-{}.json", std::process::id()));
+        let store = Store::new(path.to_str().unwrap());
+        store.with_mut(|s| {
+            s.mark_used(
+                day(2),
+                "slate".to_owned(),
+                PickSource::Queue(UserId::new(7)),
+            );
+            s.mark_used(day(1), "crane".to_owned(), PickSource::Weighted);
+            s.mark_used(day(3), "fjord".to_owned(), PickSource::Weighted);
+            s.mark_announced(day(1), None);
+            s.mark_announced(day(2), None);
+            s.mark_announced(day(3), None);
+            // announced two days ahead, and picked but not announced yet
+            s.mark_used(day(4), "pious".to_owned(), PickSource::Weighted);
+            s.mark_announced(day(4), None);
+            s.mark_used(day(5), "adieu".to_owned(), PickSource::Weighted);
+        });
+
+        // today is the 3rd, its word is still being played
+        let json = serde_json::to_string(&store.public_view(day(3))).unwrap();
+        assert_eq!(
+            json,
+            r#"{"dates":[{"date":"2024-03-01","word":"crane"},{"date":"2024-03-02","word":"slate"}]}"#
+        );
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn archived_words_stay_excluded() {
+        let dir = std::env::temp_dir();
+        let pid = std::process::id();
+        let state_path = dir.join(format!("starting-word-archive-{pid}.json"));
+        let archive_path = dir.join(format!("starting-word-archive-{pid}.ndjson.gz"));
+        let store = Store::new(state_path.to_str().unwrap());
+        store.with_mut(|s| {
+            s.mark_used(
+                day(1),
+                "crane".to_owned(),
+                PickSource::Queue(UserId::new(7)),
+            );
+            s.history[0].note = Some("my favourite".to_owned());
+            s.mark_used(day(5), "slate".to_owned(), PickSource::Weighted);
+            for e in &mut s.history {
+                e.announced = true;
+                e.revealed = true;
+            }
+        });
+
+        let moved = store
+            .archive_before(day(3), archive_path.to_str().unwrap())
+            .unwrap();
+        assert_eq!(moved, 1);
+        store.with(|s| {
+            assert_eq!(s.history.len(), 1);
+            assert!(s.used().contains("crane"));
+            assert!(s.used_since(day(1)).contains("crane"));
+            assert!(!s.used_since(day(2)).contains("crane"));
+            // rolled back to before it was used, it wasn't used yet
+            assert!(s.as_of(day(2)).used().contains("crane"));
+            assert!(!s.as_of(day(1)).used().contains("crane"));
+        });
+
+        let mut archived = String::new();
+        std::io::Read::read_to_string(
+            &mut flate2::read::MultiGzDecoder::new(fs::File::open(&archive_path).unwrap()),
+            &mut archived,
+        )
+        .unwrap();
+        let entry: UsedEntry = serde_json::from_str(archived.trim()).unwrap();
+        assert_eq!(entry.word, "crane");
+        assert_eq!(entry.suggested_by, None);
+        assert_eq!(entry.note, None);
+
+        fs::remove_file(state_path).unwrap();
+        fs::remove_file(archive_path).unwrap();
+    }
+}
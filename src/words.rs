@@ -3,47 +3,390 @@ use std::{
     collections::{HashMap, HashSet},
 };
 
+use anyhow::Context;
+use icu_normalizer::DecomposingNormalizerBorrowed;
 use rand::{
+    Rng,
     distr::{Distribution, weighted::WeightedIndex},
-    rng,
+    seq::IndexedRandom,
 };
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
 
-pub fn build_dict(path: impl AsRef<std::path::Path>) -> anyhow::Result<HashMap<String, f64>> {
-    let words: Vec<String> = std::fs::read_to_string(path)?
-        .lines()
-        .map(|s| s.trim().to_lowercase())
-        .filter(|w| w.len() == 5 && w.chars().all(|c| c.is_ascii_lowercase()))
-        .collect();
+/// Invisible characters that tend to come along when a word is copy-pasted
+const ZERO_WIDTH: [char; 6] = [
+    '\u{200b}', '\u{200c}', '\u{200d}', '\u{2060}', '\u{feff}', '\u{00ad}',
+];
 
-    let stats = compute_stats(&words);
-    let wt = Weights::default();
-    Ok(words
-        .into_iter()
-        .map(|w| {
-            let s = score_word(&w, &stats, wt);
-            (w, s)
-        })
+/// Drops invisible characters and combining marks and swaps common Cyrillic/Greek look-alikes
+/// for the ASCII letter, returning the cleaned input and whether anything was changed.
+///
+/// With `fold_accents`, combining marks are left for `normalize` to fold instead, and letters in
+/// a configured alphabet are never treated as look-alikes.
+pub fn strip_lookalikes(input: &str, opts: &DictOptions) -> (String, bool) {
+    let mut changed = false;
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        let combining = ('\u{0300}'..='\u{036f}').contains(&c);
+        if ZERO_WIDTH.contains(&c) || (combining && !opts.fold_accents) {
+            changed = true;
+        } else if let Some(ascii) =
+            homoglyph(c).filter(|_| !opts.in_alphabet(c.to_lowercase().next().unwrap_or(c)))
+        {
+            out.push(ascii);
+            changed = true;
+        } else {
+            out.push(c);
+        }
+    }
+    (out, changed)
+}
+
+/// The ASCII letter a Cyrillic or Greek character is easily mistaken for
+fn homoglyph(c: char) -> Option<char> {
+    Some(match c {
+        'а' | 'А' | 'α' | 'Α' => 'a',
+        'в' | 'В' | 'β' | 'Β' => 'b',
+        'с' | 'С' | 'ϲ' => 'c',
+        'е' | 'Е' | 'ε' | 'Ε' => 'e',
+        'н' | 'Н' | 'Η' => 'h',
+        'і' | 'І' | 'ι' | 'Ι' => 'i',
+        'ј' | 'Ј' => 'j',
+        'к' | 'К' | 'κ' | 'Κ' => 'k',
+        'м' | 'М' | 'Μ' => 'm',
+        'п' | 'η' | 'Ν' => 'n',
+        'о' | 'О' | 'ο' | 'Ο' => 'o',
+        'р' | 'Р' | 'ρ' | 'Ρ' => 'p',
+        'ѕ' | 'Ѕ' => 's',
+        'т' | 'Т' | 'τ' | 'Τ' => 't',
+        'υ' => 'u',
+        'ν' => 'v',
+        'ԝ' => 'w',
+        'х' | 'Х' | 'χ' | 'Χ' => 'x',
+        'у' | 'У' | 'γ' | 'Υ' => 'y',
+        'Ζ' => 'z',
+        _ => return None,
+    })
+}
+
+/// Canonical form of a word for dictionary lookups: trimmed and lowercased.
+///
+/// With `fold_accents`, the word is also decomposed (NFD) and combining diacritical marks
+/// (U+0300–U+036F) are dropped, so "Café" becomes "cafe".
+pub fn normalize(input: &str, fold_accents: bool) -> String {
+    let w = input.trim().to_lowercase();
+    if !fold_accents {
+        return w;
+    }
+    DecomposingNormalizerBorrowed::new_nfd()
+        .normalize(&w)
+        .chars()
+        .filter(|c| !('\u{0300}'..='\u{036f}').contains(c))
+        .collect()
+}
+
+/// Settings for how `Corpus` reads and adjusts the word list
+#[derive(Clone, Default)]
+pub struct DictOptions {
+    pub fold_accents: bool,
+    /// Words that stay valid but have `discouraged_penalty` taken off their score
+    pub discouraged: HashSet<String>,
+    pub discouraged_penalty: f64,
+    /// Names and other proper nouns to leave out of the dictionary entirely
+    pub proper_nouns: HashSet<String>,
+    /// Letters a word may use, plain a–z when unset
+    pub alphabet: Option<HashSet<char>>,
+    /// Real-world usage counts behind the `rare_word` weight
+    pub word_freq: Option<WordFreq>,
+}
+
+/// Word usage counts from an external corpus
+#[derive(Clone)]
+pub struct WordFreq {
+    counts: HashMap<String, u64>,
+    total: f64,
+}
+
+impl WordFreq {
+    /// Reads `word<TAB>count` lines, skipping any that don't parse
+    pub fn load(path: impl AsRef<std::path::Path>, fold_accents: bool) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let mut counts: HashMap<String, u64> = HashMap::new();
+        let mut skipped = 0usize;
+        for line in std::fs::read_to_string(path)
+            .with_context(|| format!("reading {}", path.display()))?
+            .lines()
+        {
+            let parsed = line.split_once('\t').and_then(|(w, c)| {
+                Some((normalize(w, fold_accents), c.trim().parse::<u64>().ok()?))
+            });
+            match parsed {
+                Some((w, c)) if !w.is_empty() => *counts.entry(w).or_default() += c,
+                _ => skipped += 1,
+            }
+        }
+        if skipped > 0 {
+            warn!("Skipped {skipped} unreadable lines in {}", path.display());
+        }
+        Ok(Self::from_counts(counts))
+    }
+
+    /// Usage counts of already normalised words
+    pub fn from_counts(counts: HashMap<String, u64>) -> Self {
+        let total = counts.values().sum::<u64>() as f64;
+        Self { counts, total }
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// ln(1/freq) of `word`, words missing from the list count as seen once, the rarest possible
+    pub fn rarity(&self, word: &str) -> f64 {
+        let count = self.counts.get(word).copied().unwrap_or(0).max(1) as f64;
+        (self.total.max(1.0) / count).ln()
+    }
+}
+
+impl DictOptions {
+    pub fn in_alphabet(&self, c: char) -> bool {
+        match &self.alphabet {
+            Some(letters) => letters.contains(&c),
+            None => c.is_ascii_lowercase(),
+        }
+    }
+
+    /// How many letters a word may use, 26 for plain a–z
+    pub fn alphabet_size(&self) -> usize {
+        self.alphabet.as_ref().map_or(26, HashSet::len)
+    }
+
+    /// Whether `word` is five letters from the allowed alphabet
+    pub fn is_valid_word(&self, word: &str) -> bool {
+        word.chars().count() == 5 && word.chars().all(|c| self.in_alphabet(c))
+    }
+}
+
+/// Reads a plain word list (one word per line) into a set of normalised words
+pub fn load_word_list(
+    path: impl AsRef<std::path::Path>,
+    fold_accents: bool,
+) -> anyhow::Result<HashSet<String>> {
+    let path = path.as_ref();
+    Ok(std::fs::read_to_string(path)
+        .with_context(|| format!("reading {}", path.display()))?
+        .lines()
+        .map(|s| normalize(s, fold_accents))
+        .filter(|w| !w.is_empty())
         .collect())
 }
 
+/// The filtered word list with its corpus statistics, kept so the dictionary can be rescored
+/// with new weights without reading the file again
+pub struct Corpus {
+    words: Vec<String>,
+    stats: Stats,
+}
+
+/// Why lines of a word list were left out, from `Corpus::load_with_report`
+#[derive(Default)]
+pub struct LoadReport {
+    pub kept: usize,
+    pub blank: usize,
+    /// Not five letters long once normalised
+    pub wrong_length: usize,
+    /// Five long, but using letters outside the alphabet
+    pub bad_letters: usize,
+    pub proper_nouns: usize,
+    pub duplicates: usize,
+}
+
+impl Corpus {
+    pub fn load(path: impl AsRef<std::path::Path>, opts: &DictOptions) -> anyhow::Result<Self> {
+        let (corpus, report) = Self::load_with_report(path, opts)?;
+        if report.duplicates > 0 {
+            warn!(
+                "Dictionary contains {} duplicate entries (after lowercasing)",
+                report.duplicates
+            );
+        }
+        if report.proper_nouns > 0 {
+            info!(
+                "Excluded {} proper nouns from the dictionary",
+                report.proper_nouns
+            );
+        }
+        Ok(corpus)
+    }
+
+    /// Loads the word list along with counts of every line that was skipped and why
+    pub fn load_with_report(
+        path: impl AsRef<std::path::Path>,
+        opts: &DictOptions,
+    ) -> anyhow::Result<(Self, LoadReport)> {
+        let path = path.as_ref();
+        let mut seen = HashSet::new();
+        let mut report = LoadReport::default();
+        let mut words: Vec<String> = Vec::new();
+        for line in std::fs::read_to_string(path)
+            .with_context(|| format!("reading {}", path.display()))?
+            .lines()
+        {
+            let w = normalize(line, opts.fold_accents);
+            if w.is_empty() {
+                report.blank += 1;
+            } else if w.chars().count() != 5 {
+                report.wrong_length += 1;
+            } else if !opts.is_valid_word(&w) {
+                report.bad_letters += 1;
+            } else if opts.proper_nouns.contains(&w) {
+                report.proper_nouns += 1;
+            } else if !seen.insert(w.clone()) {
+                report.duplicates += 1;
+            } else {
+                words.push(w);
+            }
+        }
+        report.kept = words.len();
+        Ok((Self::from_words(words, opts), report))
+    }
+
+    /// A corpus of words that are already normalised and filtered
+    pub fn from_words(words: Vec<String>, opts: &DictOptions) -> Self {
+        let stats = compute_stats(&words, opts.alphabet_size());
+        Self { words, stats }
+    }
+
+    /// Scores every word with `wt`, reusing the stats computed on load
+    pub fn rescore(&self, wt: Weights, opts: &DictOptions) -> HashMap<String, f64> {
+        self.words
+            .iter()
+            .map(|w| (w.clone(), self.breakdown(w, wt, opts).total()))
+            .collect()
+    }
+
+    /// Every term that makes up `word`'s score, including the word list adjustments. The word
+    /// doesn't need to be in the dictionary, it's scored against the same stats either way
+    pub fn breakdown(&self, word: &str, wt: Weights, opts: &DictOptions) -> ScoreBreakdown {
+        let mut breakdown = score_word(word, &self.stats, wt);
+        if let Some(freq) = &opts.word_freq {
+            breakdown.push("rare_word", freq.rarity(word), wt.rare_word);
+        }
+        if opts.discouraged.contains(word) {
+            breakdown.push("discouraged", 1.0, -opts.discouraged_penalty);
+        }
+        breakdown
+    }
+}
+
+/// A word's score as a sum of feature values times their weights
+pub struct ScoreBreakdown {
+    pub terms: Vec<ScoreTerm>,
+}
+
+pub struct ScoreTerm {
+    /// The `Weights` field, or "discouraged" for the `DISCOURAGED_PATH` penalty
+    pub name: &'static str,
+    pub value: f64,
+    pub weight: f64,
+    /// Set on a row that breaks out part of the named term. It's already counted there, so
+    /// `total` leaves it out
+    pub part_of: Option<&'static str>,
+}
+
+impl ScoreTerm {
+    pub fn product(&self) -> f64 {
+        self.value * self.weight
+    }
+}
+
+impl ScoreBreakdown {
+    fn push(&mut self, name: &'static str, value: f64, weight: f64) {
+        self.terms.push(ScoreTerm {
+            name,
+            value,
+            weight,
+            part_of: None,
+        });
+    }
+
+    fn push_part(&mut self, name: &'static str, value: f64, weight: f64, part_of: &'static str) {
+        self.terms.push(ScoreTerm {
+            name,
+            value,
+            weight,
+            part_of: Some(part_of),
+        });
+    }
+
+    pub fn total(&self) -> f64 {
+        self.terms
+            .iter()
+            .filter(|t| t.part_of.is_none())
+            .map(ScoreTerm::product)
+            .sum()
+    }
+}
+
+/// Letters that get the `rare_boost` and count towards the recent-letter penalty
+const RARE_LETTERS: [char; 8] = ['j', 'q', 'x', 'z', 'k', 'v', 'w', 'y'];
+
+/// Picks a word with probability proportional to its (alpha-powered) score times `multiplier`.
+/// With `invert` each score is replaced by `max_score - score`, so the plainest words are favoured.
+/// Words are considered in sorted order, so a seeded `rng` always gives the same pick
 pub fn pick_weighted<'a>(
     dict: &'a HashMap<String, f64>,
     exclude: Option<&HashSet<String>>,
     alpha: Option<f64>,
+    invert: bool,
+    multiplier: impl Fn(&str) -> f64,
+    rng: &mut impl Rng,
+) -> Option<&'a str> {
+    let (keys, distribution) = weighted_pool(dict, exclude, alpha, invert, multiplier)?;
+    Some(keys[distribution.sample(rng)])
+}
+
+/// Any word outside `exclude` with equal odds, ignoring scores
+pub fn pick_uniform<'a>(
+    dict: &'a HashMap<String, f64>,
+    exclude: Option<&HashSet<String>>,
+    rng: &mut impl Rng,
 ) -> Option<&'a str> {
+    let mut keys: Vec<&str> = dict
+        .keys()
+        .filter(|w| !exclude.is_some_and(|ex| ex.contains(*w)))
+        .map(String::as_str)
+        .collect();
+    keys.sort_unstable();
+    keys.choose(rng).copied()
+}
+
+/// The candidates and sampling distribution behind `pick_weighted`, for drawing many picks at once
+pub fn weighted_pool<'a>(
+    dict: &'a HashMap<String, f64>,
+    exclude: Option<&HashSet<String>>,
+    alpha: Option<f64>,
+    invert: bool,
+    multiplier: impl Fn(&str) -> f64,
+) -> Option<(Vec<&'a str>, WeightedIndex<f64>)> {
     let eps = 1e-6_f64;
+    let max_score = dict.values().copied().fold(f64::NEG_INFINITY, f64::max);
 
     let mut keys: Vec<&str> = Vec::with_capacity(dict.len());
     let mut weights: Vec<f64> = Vec::with_capacity(dict.len());
 
-    for (w, &s) in dict {
+    let mut entries: Vec<(&String, &f64)> = dict.iter().collect();
+    entries.sort_unstable_by_key(|(w, _)| *w);
+    for (w, &s) in entries {
         if exclude.is_some_and(|ex| ex.contains(w)) {
             continue;
         }
+        let s = if invert { max_score - s } else { s };
         let mut wt = s.max(0.0) + eps; // ensure positive
         if let Some(alpha) = alpha {
             wt = wt.powf(alpha);
         }
+        wt *= multiplier(w);
         if wt.is_finite() && wt > 0.0 {
             keys.push(w.as_str());
             weights.push(wt);
@@ -54,9 +397,59 @@ pub fn pick_weighted<'a>(
     }
 
     let distribution = WeightedIndex::new(&weights).ok()?;
-    let mut rng = rng();
-    let idx = distribution.sample(&mut rng);
-    Some(keys[idx])
+    Some((keys, distribution))
+}
+
+/// Rare letters appearing in any of `words`
+pub fn rare_letters<'a>(words: impl IntoIterator<Item = &'a str>) -> HashSet<char> {
+    words
+        .into_iter()
+        .flat_map(str::chars)
+        .filter(|b| RARE_LETTERS.contains(b))
+        .collect()
+}
+
+/// `penalty` raised to the number of distinct rare letters `word` shares with `recent`
+pub fn recent_letter_multiplier(word: &str, recent: &HashSet<char>, penalty: f64) -> f64 {
+    let shared = rare_letters([word]).intersection(recent).count();
+    penalty.powi(i32::try_from(shared).unwrap_or(i32::MAX))
+}
+
+/// Up to `limit` candidates within `max_dist` edits of `word`, nearest first.
+/// Only candidates sharing `word`'s first letter are compared, to keep the search cheap.
+pub fn closest_words<'a>(
+    candidates: impl IntoIterator<Item = &'a str>,
+    word: &str,
+    max_dist: usize,
+    limit: usize,
+) -> Vec<&'a str> {
+    let Some(first) = word.chars().next() else {
+        return Vec::new();
+    };
+    let mut found: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .filter(|c| c.starts_with(first))
+        .filter_map(|c| {
+            let d = levenshtein(word, c);
+            (d <= max_dist).then_some((d, c))
+        })
+        .collect();
+    found.sort_unstable();
+    found.into_iter().take(limit).map(|(_, c)| c).collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let sub = prev[j] + usize::from(ca != cb);
+            cur[j + 1] = sub.min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
 }
 
 #[allow(dead_code)]
@@ -79,14 +472,23 @@ pub fn print_top(dict: &HashMap<String, f64>, n: usize, top: bool) {
 struct Stats {
     letter_ct: HashMap<char, usize>,
     bigram_ct: HashMap<(char, char), usize>,
+    trigram_ct: HashMap<(char, char, char), usize>,
+    /// Number of dictionary words sharing each sorted-letter multiset
+    anagram_ct: HashMap<String, usize>,
     total_letters: f64,
     total_bigrams: f64,
+    total_trigrams: f64,
+    /// Number of trigrams the alphabet can spell, the denominator's smoothing
+    possible_trigrams: f64,
 }
 
-fn compute_stats(words: &[String]) -> Stats {
+fn compute_stats(words: &[String], alphabet_size: usize) -> Stats {
     let mut letter_ct = HashMap::new();
     let mut bigram_ct = HashMap::new();
+    let mut trigram_ct = HashMap::new();
+    let mut anagram_ct = HashMap::new();
     for w in words {
+        *anagram_ct.entry(anagram_key(w)).or_default() += 1;
         let chars: Vec<char> = w.chars().collect();
         for &c in &chars {
             *letter_ct.entry(c).or_default() += 1;
@@ -94,21 +496,39 @@ fn compute_stats(words: &[String]) -> Stats {
         for i in 0..4 {
             *bigram_ct.entry((chars[i], chars[i + 1])).or_default() += 1;
         }
+        for i in 0..3 {
+            *trigram_ct
+                .entry((chars[i], chars[i + 1], chars[i + 2]))
+                .or_default() += 1;
+        }
     }
     Stats {
         total_letters: (words.len() as f64) * 5.0,
         total_bigrams: (words.len() as f64) * 4.0,
+        total_trigrams: (words.len() as f64) * 3.0,
+        possible_trigrams: (alphabet_size as f64).powi(3),
         letter_ct,
         bigram_ct,
+        trigram_ct,
+        anagram_ct,
     }
 }
 
-#[derive(Clone, Copy)]
-struct Weights {
+fn anagram_key(word: &str) -> String {
+    let mut chars: Vec<char> = word.chars().collect();
+    chars.sort_unstable();
+    chars.into_iter().collect()
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Weights {
     // corpus
-    pub rare_letter: f64, // ln(1/freq) per letter
-    pub rare_boost: f64,  // extra for jqxzkvwy per letter
-    pub rare_bigram: f64, // ln(1/freq) per bigram
+    pub rare_letter: f64,  // ln(1/freq) per letter
+    pub rare_boost: f64,   // extra for jqxzkvwy per letter
+    pub rare_bigram: f64,  // ln(1/freq) per bigram
+    pub rare_trigram: f64, // ln(1/freq) per trigram, smoothed
+    pub rare_word: f64,    // ln(1/freq) of the word itself in FREQ_PATH, if set
 
     // shared/local features
     pub no_vowels_y: f64, // no AEIOUY
@@ -121,6 +541,14 @@ struct Weights {
     pub ababa: f64, // ABABA pattern
     pub repeated_bigram: f64,
     pub q_without_u: f64,
+    pub unique_anagram: f64,  // 1 / number of words sharing these letters
+    pub keyboard_run: f64,    // per step in the longest run of neighbouring QWERTY keys
+    pub vowels_adjacent: f64, // 2+ vowels, all in one unbroken block
+    pub edge_vowels: f64,     // vowels only in the first/last position, none in positions 2-4
+    pub start_double: f64,    // extra for a double in positions 1-2, on top of adj_double
+    pub end_double: f64,      // extra for a double in positions 4-5, on top of adj_double
+    pub irregular_cv: f64,    // per neighbouring pair that breaks consonant/vowel alternation
+    pub all_unique: f64,      // five distinct letters, negative to push the picker away from them
 }
 
 impl Default for Weights {
@@ -129,6 +557,8 @@ impl Default for Weights {
             rare_letter: 0.35,
             rare_boost: 0.25,
             rare_bigram: 0.20,
+            rare_trigram: 0.0,
+            rare_word: 0.15,
             no_vowels_y: 9.0,
             no_vowels: 5.0,
             low_vowel_ratio: 2.0,
@@ -139,46 +569,137 @@ impl Default for Weights {
             ababa: 3.0,
             repeated_bigram: 1.2,
             q_without_u: 2.0,
+            unique_anagram: 0.0,
+            keyboard_run: 0.0,
+            vowels_adjacent: 0.0,
+            edge_vowels: 0.0,
+            start_double: 0.0,
+            end_double: 0.0,
+            irregular_cv: 0.0,
+            all_unique: 0.0,
         }
     }
 }
 
-fn score_word(word: &str, stats: &Stats, wt: Weights) -> f64 {
-    let b = word.as_bytes();
+impl Weights {
+    pub const NAMES: [&'static str; 23] = [
+        "rare_letter",
+        "rare_boost",
+        "rare_bigram",
+        "rare_trigram",
+        "rare_word",
+        "no_vowels_y",
+        "no_vowels",
+        "low_vowel_ratio",
+        "adj_double",
+        "max_cons_cluster",
+        "dup_extra",
+        "low_unique",
+        "ababa",
+        "repeated_bigram",
+        "q_without_u",
+        "unique_anagram",
+        "keyboard_run",
+        "vowels_adjacent",
+        "edge_vowels",
+        "start_double",
+        "end_double",
+        "irregular_cv",
+        "all_unique",
+    ];
+
+    /// Looks up a weight by its field name
+    pub fn field_mut(&mut self, name: &str) -> Option<&mut f64> {
+        Some(match name {
+            "rare_letter" => &mut self.rare_letter,
+            "rare_boost" => &mut self.rare_boost,
+            "rare_bigram" => &mut self.rare_bigram,
+            "rare_trigram" => &mut self.rare_trigram,
+            "rare_word" => &mut self.rare_word,
+            "no_vowels_y" => &mut self.no_vowels_y,
+            "no_vowels" => &mut self.no_vowels,
+            "low_vowel_ratio" => &mut self.low_vowel_ratio,
+            "adj_double" => &mut self.adj_double,
+            "max_cons_cluster" => &mut self.max_cons_cluster,
+            "dup_extra" => &mut self.dup_extra,
+            "low_unique" => &mut self.low_unique,
+            "ababa" => &mut self.ababa,
+            "repeated_bigram" => &mut self.repeated_bigram,
+            "q_without_u" => &mut self.q_without_u,
+            "unique_anagram" => &mut self.unique_anagram,
+            "keyboard_run" => &mut self.keyboard_run,
+            "vowels_adjacent" => &mut self.vowels_adjacent,
+            "edge_vowels" => &mut self.edge_vowels,
+            "start_double" => &mut self.start_double,
+            "end_double" => &mut self.end_double,
+            "irregular_cv" => &mut self.irregular_cv,
+            "all_unique" => &mut self.all_unique,
+            _ => return None,
+        })
+    }
+
+    /// Loads weights from a JSON file, any missing fields keep their default value
+    pub fn load(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let buffer = serde_json::to_vec_pretty(self)?;
+        std::fs::write(path, buffer).with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+fn score_word(word: &str, stats: &Stats, wt: Weights) -> ScoreBreakdown {
+    let b: Vec<char> = word.chars().collect();
     let eps = 1e-6_f64;
-    let rare = [b'j', b'q', b'x', b'z', b'k', b'v', b'w', b'y'];
 
     // vowels
-    let has_v = b
-        .iter()
-        .any(|&c| matches!(c, b'a' | b'e' | b'i' | b'o' | b'u'));
+    let has_v = b.iter().any(|&c| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u'));
     let has_vy = b
         .iter()
-        .any(|&c| matches!(c, b'a' | b'e' | b'i' | b'o' | b'u' | b'y'));
+        .any(|&c| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y'));
     let vowel_ratio = b
         .iter()
-        .filter(|&&c| matches!(c, b'a' | b'e' | b'i' | b'o' | b'u'))
+        .filter(|&&c| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u'))
         .count() as f64
         / 5.0;
 
+    // vowel layout: piled into one block ("queue") or pushed to the ends ("ultra" style)
+    let vowel_idx: Vec<usize> = (0..5)
+        .filter(|&i| matches!(b[i], 'a' | 'e' | 'i' | 'o' | 'u'))
+        .collect();
+    let vowels_adjacent = f64::from(u8::from(
+        vowel_idx.len() >= 2
+            && vowel_idx[vowel_idx.len() - 1] - vowel_idx[0] == vowel_idx.len() - 1,
+    ));
+    let edge_vowels = f64::from(u8::from(
+        !vowel_idx.is_empty() && vowel_idx.iter().all(|&i| i == 0 || i == 4),
+    ));
+
     // counts and repeats
-    let mut cnt = [0u8; 26];
-    for &c in b {
-        cnt[(c - b'a') as usize] += 1;
+    let mut cnt: HashMap<char, u8> = HashMap::new();
+    for &c in &b {
+        *cnt.entry(c).or_default() += 1;
     }
-    let unique = i32::try_from(cnt.iter().filter(|&&k| k > 0).count())
-        .expect("Score word failed, cnt value too large");
-    let dup_total: i32 = cnt.iter().map(|&k| i32::from(k.saturating_sub(1))).sum();
+    let unique = i32::try_from(cnt.len()).expect("Score word failed, cnt value too large");
+    let dup_total: i32 = cnt.values().map(|&k| i32::from(k.saturating_sub(1))).sum();
 
-    // adjacent doubles
-    let adj_doubles = (0..4).filter(|&i| b[i] == b[i + 1]).count() as f64;
+    // adjacent doubles, by the index of their first letter. A leading double ("llama") is far
+    // rarer than one mid-word ("belle") or at the end ("grass")
+    let double_at: Vec<usize> = (0..4).filter(|&i| b[i] == b[i + 1]).collect();
+    let adj_doubles = double_at.len() as f64;
+    let start_double = f64::from(u8::from(double_at.contains(&0)));
+    let end_double = f64::from(u8::from(double_at.contains(&3)));
 
     // max consonant cluster (y treated as vowel)
     let mut best = 0;
     let mut cur = 0;
-    for &c in b {
+    for &c in &b {
         match c {
-            b'a' | b'e' | b'i' | b'o' | b'u' | b'y' => cur = 0,
+            'a' | 'e' | 'i' | 'o' | 'u' | 'y' => cur = 0,
             _ => {
                 cur += 1;
                 best = best.max(cur);
@@ -186,6 +707,11 @@ fn score_word(word: &str, stats: &Stats, wt: Weights) -> f64 {
         }
     }
 
+    // consonant/vowel rhythm: "cabin" alternates strictly, "stretch"-style runs break it. Counts
+    // neighbouring letters of the same kind (y treated as vowel)
+    let is_v = |c: char| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y');
+    let cv_breaks = (0..4).filter(|&i| is_v(b[i]) == is_v(b[i + 1])).count() as f64;
+
     // ABABA pattern (0=2=4 and 1=3, and a!=b)
     let ababa = f64::from(i32::from(
         b[0] == b[2] && b[2] == b[4] && b[0] != b[1] && b[1] == b[3],
@@ -208,45 +734,459 @@ fn score_word(word: &str, stats: &Stats, wt: Weights) -> f64 {
         0.0
     };
 
-    // corpus rarity (letters + bigrams)
+    // anagram uniqueness: words with no anagrams in the dictionary get the full term
+    let anagram_group = *stats.anagram_ct.get(&anagram_key(word)).unwrap_or(&1);
+    let unique_anagram = 1.0 / anagram_group.max(1) as f64;
+
+    // keyboard smash: longest run of letters that are side by side on a QWERTY row
+    let mut keyboard_run = 0;
+    let mut run = 0;
+    for i in 0..4 {
+        let neighbours = match (key_position(b[i]), key_position(b[i + 1])) {
+            (Some((r1, c1)), Some((r2, c2))) => r1 == r2 && c1.abs_diff(c2) == 1,
+            _ => false,
+        };
+        run = if neighbours { run + 1 } else { 0 };
+        keyboard_run = keyboard_run.max(run);
+    }
+
+    // corpus rarity (letters + bigrams + trigrams)
     let mut rare_letter_score = 0.0;
-    for &bb in b {
-        let c = bb as char;
+    for &c in &b {
         let f = (*stats.letter_ct.get(&c).unwrap_or(&1) as f64 / stats.total_letters).max(eps);
         rare_letter_score += (1.0 / f).ln()
-            + if rare.contains(&bb) {
+            + if RARE_LETTERS.contains(&c) {
                 wt.rare_boost
             } else {
                 0.0
             };
     }
+    let rare_boost = b.iter().filter(|c| RARE_LETTERS.contains(c)).count() as f64;
     let mut rare_bigram_score = 0.0;
     for i in 0..4 {
-        let k = (b[i] as char, b[i + 1] as char);
+        let k = (b[i], b[i + 1]);
         let f = (*stats.bigram_ct.get(&k).unwrap_or(&1) as f64 / stats.total_bigrams).max(eps);
         rare_bigram_score += (1.0 / f).ln();
     }
+    // add-one smoothed over every possible trigram, most are unseen in a small word list and
+    // would otherwise all share the same huge ln(1/eps)
+    let mut rare_trigram_score = 0.0;
+    for i in 0..3 {
+        let k = (b[i], b[i + 1], b[i + 2]);
+        let ct = *stats.trigram_ct.get(&k).unwrap_or(&0) as f64;
+        let f = (ct + 1.0) / (stats.total_trigrams + stats.possible_trigrams);
+        rare_trigram_score += (1.0 / f).ln();
+    }
 
     // combine
-    let mut score = 0.0;
-    if !has_vy {
-        score += wt.no_vowels_y;
-    } else if !has_v {
-        score += wt.no_vowels;
-    }
-    if vowel_ratio < 0.2 {
-        score += wt.low_vowel_ratio;
-    }
-
-    score += wt.rare_letter * rare_letter_score;
-    score += wt.rare_bigram * rare_bigram_score;
-    score += wt.adj_double * adj_doubles;
-    score += wt.max_cons_cluster * f64::from(best);
-    score += wt.dup_extra * f64::from(dup_total);
-    score += wt.low_unique * f64::from((5 - unique).max(0));
-    score += wt.ababa * ababa;
-    score += wt.repeated_bigram * repeated_bg;
-    score += wt.q_without_u * q_without_u;
+    let flag = |b: bool| f64::from(u8::from(b));
+    let mut score = ScoreBreakdown { terms: Vec::new() };
+    score.push("no_vowels_y", flag(!has_vy), wt.no_vowels_y);
+    score.push("no_vowels", flag(has_vy && !has_v), wt.no_vowels);
+    score.push(
+        "low_vowel_ratio",
+        flag(vowel_ratio < 0.2),
+        wt.low_vowel_ratio,
+    );
+
+    score.push("rare_letter", rare_letter_score, wt.rare_letter);
+    // the boost is inside rare_letter's value, shown on its own for /inspect
+    score.push_part(
+        "rare_boost",
+        rare_boost,
+        wt.rare_letter * wt.rare_boost,
+        "rare_letter",
+    );
+    score.push("rare_bigram", rare_bigram_score, wt.rare_bigram);
+    score.push("rare_trigram", rare_trigram_score, wt.rare_trigram);
+    score.push("adj_double", adj_doubles, wt.adj_double);
+    score.push("max_cons_cluster", f64::from(best), wt.max_cons_cluster);
+    score.push("dup_extra", f64::from(dup_total), wt.dup_extra);
+    score.push("low_unique", f64::from((5 - unique).max(0)), wt.low_unique);
+    score.push("ababa", ababa, wt.ababa);
+    score.push("repeated_bigram", repeated_bg, wt.repeated_bigram);
+    score.push("q_without_u", q_without_u, wt.q_without_u);
+    score.push("unique_anagram", unique_anagram, wt.unique_anagram);
+    score.push("keyboard_run", f64::from(keyboard_run), wt.keyboard_run);
+    score.push("vowels_adjacent", vowels_adjacent, wt.vowels_adjacent);
+    score.push("edge_vowels", edge_vowels, wt.edge_vowels);
+    score.push("start_double", start_double, wt.start_double);
+    score.push("end_double", end_double, wt.end_double);
+    score.push("irregular_cv", cv_breaks, wt.irregular_cv);
+    score.push("all_unique", flag(unique == 5), wt.all_unique);
 
     score
 }
+
+/// Row and column of a letter on a QWERTY keyboard
+fn key_position(c: char) -> Option<(usize, usize)> {
+    const ROWS: [&str; 3] = ["qwertyuiop", "asdfghjkl", "zxcvbnm"];
+    ROWS.iter()
+        .enumerate()
+        .find_map(|(row, keys)| keys.find(c).map(|col| (row, col)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus(words: &[&str]) -> Corpus {
+        Corpus::from_words(
+            words.iter().map(|w| (*w).to_owned()).collect(),
+            &DictOptions::default(),
+        )
+    }
+
+    /// The raw value of the `name` term in `word`'s score
+    fn term(corpus: &Corpus, word: &str, name: &str) -> f64 {
+        corpus
+            .breakdown(word, Weights::default(), &DictOptions::default())
+            .terms
+            .iter()
+            .find(|t| t.name == name)
+            .map(|t| t.value)
+            .unwrap()
+    }
+
+    /// How much `word`'s score moves when the `name` weight is set to `weight`
+    fn effect(corpus: &Corpus, word: &str, name: &str, weight: f64) -> f64 {
+        let opts = DictOptions::default();
+        let mut wt = Weights::default();
+        let before = corpus.breakdown(word, wt, &opts).total();
+        *wt.field_mut(name).unwrap() = weight;
+        corpus.breakdown(word, wt, &opts).total() - before
+    }
+
+    #[test]
+    fn unique_anagram_favours_words_without_anagrams() {
+        let c = corpus(&["crane", "nacre", "fjord"]);
+        assert_eq!(term(&c, "crane", "unique_anagram"), 0.5);
+        assert_eq!(term(&c, "fjord", "unique_anagram"), 1.0);
+        assert_eq!(effect(&c, "crane", "unique_anagram", 0.0), 0.0);
+        assert!((effect(&c, "fjord", "unique_anagram", 2.0) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rare_trigram_scores_unseen_trigrams_higher() {
+        let c = corpus(&["crane", "crank", "crate", "fjord"]);
+        assert!(term(&c, "crane", "rare_trigram") < term(&c, "fjord", "rare_trigram"));
+        // smoothing keeps a trigram no word has finite
+        assert!(term(&c, "zzzzz", "rare_trigram").is_finite());
+        assert_eq!(effect(&c, "fjord", "rare_trigram", 0.0), 0.0);
+    }
+
+    #[test]
+    fn custom_alphabet_admits_its_letters() {
+        let plain = DictOptions::default();
+        assert!(plain.is_valid_word("crane"));
+        assert!(!plain.is_valid_word("niñez"));
+        assert_eq!(plain.alphabet_size(), 26);
+
+        let spanish = DictOptions {
+            alphabet: Some("abcdefghijklmnñopqrstuvwxyz".chars().collect()),
+            ..DictOptions::default()
+        };
+        assert!(spanish.is_valid_word("niñez"));
+        assert!(!spanish.is_valid_word("crâne"));
+        assert_eq!(spanish.alphabet_size(), 27);
+
+        // a bigger alphabet spreads the smoothing thinner, so an unseen trigram gets rarer
+        let words = vec!["crane".to_owned(), "niñez".to_owned()];
+        let small = Corpus::from_words(words.clone(), &plain);
+        let big = Corpus::from_words(words, &spanish);
+        let rarity = |c: &Corpus, opts| {
+            c.breakdown("fjord", Weights::default(), opts)
+                .terms
+                .into_iter()
+                .find(|t| t.name == "rare_trigram")
+                .unwrap()
+                .value
+        };
+        assert!(rarity(&big, &spanish) > rarity(&small, &plain));
+    }
+
+    #[test]
+    fn keyboard_run_counts_steps_along_a_row() {
+        let c = corpus(&["qwert", "fjord"]);
+        assert_eq!(term(&c, "qwert", "keyboard_run"), 4.0);
+        assert_eq!(term(&c, "asdfj", "keyboard_run"), 3.0);
+        assert_eq!(term(&c, "fjord", "keyboard_run"), 0.0);
+        assert_eq!(effect(&c, "qwert", "keyboard_run", 0.5), 2.0);
+    }
+
+    #[test]
+    fn vowel_layout_terms() {
+        let c = corpus(&["queue", "cabin", "ultra"]);
+        assert_eq!(term(&c, "queue", "vowels_adjacent"), 1.0);
+        assert_eq!(term(&c, "cabin", "vowels_adjacent"), 0.0);
+        // a lone vowel isn't a block
+        assert_eq!(term(&c, "crwth", "vowels_adjacent"), 0.0);
+        assert_eq!(term(&c, "ultra", "edge_vowels"), 1.0);
+        assert_eq!(term(&c, "cabin", "edge_vowels"), 0.0);
+        assert_eq!(effect(&c, "queue", "vowels_adjacent", 1.0), 1.0);
+        assert_eq!(effect(&c, "ultra", "edge_vowels", 1.5), 1.5);
+    }
+
+    #[test]
+    fn doubles_at_the_edges_score_separately() {
+        let c = corpus(&["llama", "belle", "grass"]);
+        assert_eq!(term(&c, "llama", "start_double"), 1.0);
+        assert_eq!(term(&c, "llama", "end_double"), 0.0);
+        assert_eq!(term(&c, "grass", "end_double"), 1.0);
+        assert_eq!(term(&c, "grass", "start_double"), 0.0);
+        assert_eq!(term(&c, "belle", "start_double"), 0.0);
+        assert_eq!(term(&c, "belle", "end_double"), 0.0);
+        assert_eq!(effect(&c, "llama", "start_double", 1.5), 1.5);
+        assert_eq!(effect(&c, "grass", "end_double", 0.25), 0.25);
+    }
+
+    #[test]
+    fn irregular_cv_counts_same_kind_neighbours() {
+        let c = corpus(&["cabin", "strap"]);
+        assert_eq!(term(&c, "cabin", "irregular_cv"), 0.0);
+        // s-t and t-r
+        assert_eq!(term(&c, "strap", "irregular_cv"), 2.0);
+        // y counts as a vowel
+        assert_eq!(term(&c, "gypsy", "irregular_cv"), 1.0);
+        assert_eq!(effect(&c, "strap", "irregular_cv", 0.5), 1.0);
+    }
+
+    #[test]
+    fn discouraged_words_drop_below_their_peers() {
+        let c = corpus(&["crane", "trace"]);
+        let plain = DictOptions::default();
+        let tiered = DictOptions {
+            discouraged: ["crane".to_owned()].into(),
+            discouraged_penalty: 3.0,
+            ..DictOptions::default()
+        };
+        let before = c.rescore(Weights::default(), &plain);
+        let after = c.rescore(Weights::default(), &tiered);
+        assert!((before["crane"] - after["crane"] - 3.0).abs() < 1e-9);
+        assert_eq!(before["trace"], after["trace"]);
+        // still in the dictionary, just less likely
+        assert!(after.contains_key("crane"));
+    }
+
+    #[test]
+    fn recent_rare_letters_scale_a_words_weight() {
+        let recent = rare_letters(["jazzy", "crane"]);
+        assert_eq!(recent, ['j', 'z', 'y'].into());
+        assert_eq!(recent_letter_multiplier("crane", &recent, 0.5), 1.0);
+        assert_eq!(recent_letter_multiplier("fizzy", &recent, 0.5), 0.25);
+
+        let dict: HashMap<String, f64> =
+            [("crane".to_owned(), 1.0), ("fizzy".to_owned(), 1.0)].into();
+        let (keys, dist) = weighted_pool(&dict, None, None, false, |w| {
+            recent_letter_multiplier(w, &recent, 0.5)
+        })
+        .unwrap();
+        let weights: Vec<f64> = dist.weights().collect();
+        let weight_of = |w| weights[keys.iter().position(|k| *k == w).unwrap()];
+        assert!((weight_of("crane") / weight_of("fizzy") - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn closest_words_are_nearest_first_and_share_the_first_letter() {
+        let candidates = ["crane", "crate", "grace", "crone", "cacao", "trace"];
+        assert_eq!(closest_words(candidates, "crame", 1, 5), ["crane", "crate"]);
+        assert_eq!(closest_words(candidates, "crane", 2, 2), ["crane", "crate"]);
+        // "grace" and "trace" are close but start differently
+        assert_eq!(closest_words(candidates, "brace", 2, 5), Vec::<&str>::new());
+        assert!(closest_words(candidates, "", 5, 5).is_empty());
+    }
+
+    #[test]
+    fn invert_favours_the_lowest_scores() {
+        let dict: HashMap<String, f64> = [
+            ("crane".to_owned(), 1.0),
+            ("slate".to_owned(), 2.0),
+            ("fjord".to_owned(), 5.0),
+        ]
+        .into();
+        let ranking = |invert| {
+            let (keys, dist) = weighted_pool(&dict, None, None, invert, |_| 1.0).unwrap();
+            let weights: Vec<f64> = dist.weights().collect();
+            let mut ranked: Vec<_> = keys.into_iter().zip(weights).collect();
+            ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+            ranked.into_iter().map(|(k, _)| k).collect::<Vec<_>>()
+        };
+        assert_eq!(ranking(false), ["fjord", "slate", "crane"]);
+        assert_eq!(ranking(true), ["crane", "slate", "fjord"]);
+
+        // with everything but the top word excluded the inverted pool still has it to give
+        let rest: HashSet<String> = ["crane".to_owned(), "slate".to_owned()].into();
+        let mut rng = rand::rng();
+        assert_eq!(
+            pick_weighted(&dict, Some(&rest), None, true, |_| 1.0, &mut rng),
+            Some("fjord")
+        );
+    }
+
+    #[test]
+    fn strip_lookalikes_drops_zero_width_and_swaps_homoglyphs() {
+        let opts = DictOptions::default();
+        assert_eq!(
+            strip_lookalikes("cr\u{200b}a\u{feff}ne", &opts),
+            ("crane".to_owned(), true)
+        );
+        // Cyrillic а and е
+        assert_eq!(
+            strip_lookalikes("cr\u{0430}n\u{0435}", &opts),
+            ("crane".to_owned(), true)
+        );
+        assert_eq!(
+            strip_lookalikes("crane", &opts),
+            ("crane".to_owned(), false)
+        );
+        // a letter the alphabet allows is kept even when it looks like another
+        let greek = DictOptions {
+            alphabet: Some("αβγδε".chars().collect()),
+            ..DictOptions::default()
+        };
+        assert_eq!(strip_lookalikes("αβγ", &greek), ("αβγ".to_owned(), false));
+    }
+
+    #[test]
+    fn rescore_follows_the_weights() {
+        let c = corpus(&["qwert", "fjord", "cabin"]);
+        let opts = DictOptions::default();
+        let base = c.rescore(Weights::default(), &opts);
+        let wt = Weights {
+            keyboard_run: 2.0,
+            ..Weights::default()
+        };
+        let boosted = c.rescore(wt, &opts);
+        assert!((boosted["qwert"] - base["qwert"] - 8.0).abs() < 1e-9);
+        assert_eq!(boosted["fjord"], base["fjord"]);
+        // the same as scoring each word from scratch
+        for (w, s) in &boosted {
+            assert_eq!(*s, c.breakdown(w, wt, &opts).total());
+        }
+    }
+
+    #[test]
+    fn rare_word_blends_in_usage_counts() {
+        let c = corpus(&["crane", "fjord"]);
+        let freq =
+            WordFreq::from_counts([("crane".to_owned(), 99), ("fjord".to_owned(), 1)].into());
+        assert_eq!(freq.rarity("crane"), (100f64 / 99.0).ln());
+        assert_eq!(freq.rarity("fjord"), 100f64.ln());
+        // unlisted words count as seen once
+        assert_eq!(freq.rarity("qwert"), freq.rarity("fjord"));
+
+        let wt = Weights::default();
+        let plain = DictOptions::default();
+        let opts = DictOptions {
+            word_freq: Some(freq),
+            ..DictOptions::default()
+        };
+        let added = |w| c.breakdown(w, wt, &opts).total() - c.breakdown(w, wt, &plain).total();
+        assert!((added("fjord") - wt.rare_word * 100f64.ln()).abs() < 1e-9);
+        assert!(added("fjord") > added("crane"));
+        // no term at all without a frequency list
+        assert!(
+            !c.breakdown("fjord", wt, &plain)
+                .terms
+                .iter()
+                .any(|t| t.name == "rare_word")
+        );
+    }
+
+    #[test]
+    fn pick_uniform_spreads_evenly_over_unused_words() {
+        use rand::SeedableRng;
+
+        let dict: HashMap<String, f64> = [
+            ("crane", 9.0),
+            ("slate", 0.1),
+            ("fjord", 3.0),
+            ("adieu", 1.0),
+        ]
+        .into_iter()
+        .map(|(w, s)| (w.to_owned(), s))
+        .collect();
+        let used: HashSet<String> = ["adieu".to_owned()].into();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for _ in 0..3000 {
+            *counts
+                .entry(pick_uniform(&dict, Some(&used), &mut rng).unwrap())
+                .or_default() += 1;
+        }
+        assert!(!counts.contains_key("adieu"));
+        // scores play no part, each of the three gets about a third
+        for w in ["crane", "slate", "fjord"] {
+            assert!((900..1100).contains(&counts[w]), "{w}: {}", counts[w]);
+        }
+        // the same seed draws the same word
+        let draw = |seed| {
+            pick_uniform(
+                &dict,
+                Some(&used),
+                &mut rand::rngs::StdRng::seed_from_u64(seed),
+            )
+        };
+        assert_eq!(draw(3), draw(3));
+    }
+
+    #[test]
+    fn rare_boost_is_shown_inside_rare_letter() {
+        let c = corpus(&["jazzy", "crane"]);
+        assert_eq!(term(&c, "jazzy", "rare_boost"), 4.0);
+        assert_eq!(term(&c, "crane", "rare_boost"), 0.0);
+        let wt = Weights::default();
+        let boost = c
+            .breakdown("jazzy", wt, &DictOptions::default())
+            .terms
+            .into_iter()
+            .find(|t| t.name == "rare_boost")
+            .unwrap();
+        assert_eq!(boost.part_of, Some("rare_letter"));
+        assert_eq!(boost.weight, wt.rare_letter * wt.rare_boost);
+        // it scales with rare_letter, as it always has
+        let expected = 4.0 * wt.rare_letter * -wt.rare_boost;
+        assert!((effect(&c, "jazzy", "rare_boost", 0.0) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn default_totals_match_the_original_formula() {
+        let c = corpus(&["jazzy", "crane", "fjord", "queue", "llama"]);
+        let wt = Weights::default();
+        for word in ["jazzy", "crane", "fjord", "qwert", "xylyl"] {
+            let breakdown = c.breakdown(word, wt, &DictOptions::default());
+            // rare_letter as it was computed before the breakdown, boost folded into each letter
+            let mut rare_letter = 0.0;
+            for ch in word.chars() {
+                let f = (*c.stats.letter_ct.get(&ch).unwrap_or(&1) as f64 / c.stats.total_letters)
+                    .max(1e-6);
+                rare_letter += (1.0 / f).ln()
+                    + if RARE_LETTERS.contains(&ch) {
+                        wt.rare_boost
+                    } else {
+                        0.0
+                    };
+            }
+            let mut expected = 0.0;
+            for t in breakdown.terms.iter().filter(|t| t.name != "rare_boost") {
+                expected += if t.name == "rare_letter" {
+                    wt.rare_letter * rare_letter
+                } else {
+                    t.weight * t.value
+                };
+            }
+            assert_eq!(breakdown.total().to_bits(), expected.to_bits(), "{word}");
+        }
+    }
+
+    #[test]
+    fn all_unique_flags_five_distinct_letters() {
+        let c = corpus(&["crane", "belle"]);
+        assert_eq!(term(&c, "crane", "all_unique"), 1.0);
+        assert_eq!(term(&c, "belle", "all_unique"), 0.0);
+        // off by default, and a negative weight pushes distinct-letter words down
+        assert_eq!(Weights::default().all_unique, 0.0);
+        assert_eq!(effect(&c, "crane", "all_unique", -2.0), -2.0);
+        assert_eq!(effect(&c, "belle", "all_unique", -2.0), 0.0);
+    }
+}